@@ -0,0 +1,98 @@
+// Misst Filtern und Zeichnen der Befehlsliste ohne Fenster: `cargo bench --bench command_list`
+#[path = "../src/command_list.rs"]
+mod command_list;
+
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+const COMMAND_COUNT: usize = 20_000;
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+fn synthetic_commands() -> Vec<String> {
+    let prefixes = ["git", "docker", "python3", "gcc", "x86_64-linux-gnu", "nix", "cargo", "kube", "systemd", "ls"];
+    let suffixes = ["", "-config", "-daemon", "ctl", "-helper", "-dump", "-check", "-run"];
+    let mut commands: Vec<String> = (0..COMMAND_COUNT)
+        .map(|i| {
+            let prefix = prefixes[i % prefixes.len()];
+            let suffix = suffixes[(i / prefixes.len()) % suffixes.len()];
+            format!("{}{}-{}", prefix, suffix, i)
+        })
+        .collect();
+    // Echte Namen, auf die sich Hervorhebung, aufgeklappte Unterbefehle und Varianten beziehen
+    commands.extend(["git", "gitk"].map(String::from));
+    commands.sort_by_key(|cmd| cmd.len());
+    commands
+}
+
+fn render_frame(ctx: &egui::Context, commands: &[String], selected: Option<&str>) -> Duration {
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1280.0, 800.0))),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let _ = ctx.run(input, |ctx| {
+        egui::SidePanel::left("side_panel").show(ctx, |ui| {
+            // Hervorhebungen wie nach einer Paketinstallation und eingerückte Varianten mitmessen
+            let style = |name: &str| command_list::RowStyle {
+                change: match name {
+                    "git" => Some(command_list::RowChange::Added),
+                    "gitk" => Some(command_list::RowChange::Removed),
+                    _ => None,
+                },
+                nested: name.starts_with("python3-config"),
+                ..Default::default()
+            };
+            let clicked = command_list::show(ui, commands, selected, true, style);
+            // Ohne Eingaben wird nie geklickt; black_box verhindert, dass der Aufruf wegoptimiert wird
+            if let Some(
+                command_list::ListClick::Select(command) | command_list::ListClick::OpenInTab(command) | command_list::ListClick::Toggle(command),
+            ) = clicked {
+                std::hint::black_box(command);
+            }
+        });
+    });
+    start.elapsed()
+}
+
+fn main() {
+    let commands = synthetic_commands();
+    let ctx = egui::Context::default();
+    let mut matched = commands.clone();
+    let mut filtered = commands.clone();
+    let mut previous_query = String::new();
+    let subcommands = HashMap::from([("git".to_string(), vec!["commit".to_string(), "rebase".to_string()])]);
+    let expanded = HashSet::from(["git".to_string()]);
+    // Jede zweite python3-Variante hängt an einem Elterneintrag, wie es variants::group liefern würde
+    let variant_parents: HashMap<String, String> = commands
+        .iter()
+        .filter(|command| command.starts_with("python3-config"))
+        .step_by(2)
+        .map(|command| (command.clone(), "python3-2".to_string()))
+        .collect();
+    let variants = HashMap::from([("python3-2".to_string(), variant_parents.keys().cloned().collect::<Vec<_>>())]);
+
+    // Erster Frame lädt die Schriften und zählt nicht
+    render_frame(&ctx, &filtered, None);
+
+    let keystrokes = ["g", "gi", "git", "git-", "git-c", "git-co", "git-c", "git-", "", "x", "x86", "x86_64-linux", "git", "git re"];
+    let mut slowest = Duration::ZERO;
+    println!("{:<16} {:>8} {:>12} {:>12}", "query", "matches", "filter", "frame");
+    for query in keystrokes {
+        let start = Instant::now();
+        command_list::filter(&commands, &mut matched, Some(&previous_query), query);
+        filtered = command_list::with_children(&matched, &subcommands, &variants, &variant_parents, &expanded, query);
+        let filter_time = start.elapsed();
+        previous_query = query.to_string();
+
+        let selected = filtered.first().map(String::as_str);
+        let frame_time = render_frame(&ctx, &filtered, selected);
+        slowest = slowest.max(filter_time + frame_time);
+        println!("{:<16} {:>8} {:>12?} {:>12?}", format!("{:?}", query), filtered.len(), filter_time, frame_time);
+    }
+
+    println!("{} commands, slowest keystroke {:?} (budget {:?})", commands.len(), slowest, FRAME_BUDGET);
+    if slowest > FRAME_BUDGET {
+        println!("WARNING: keystroke exceeded the frame budget");
+    }
+}
@@ -1,100 +1,552 @@
-use crate::cli::get_cli_commands;
-use crate::manpage::get_manpage;
+use crate::builder::CommandBuilder;
+use crate::cli::{command_path, get_cli_commands, is_command_name};
+use crate::categories::{self, Category, CategoryIndex};
+use crate::command_filter::{self, CommandDirs, CommandKind, FilterRule, FilterStats, FILTER_FILE};
+use crate::command_list::{self, RowChange};
+use crate::completion::{self, CompletionSpec, SpecFile};
+use crate::config;
+use crate::documentation::{DocumentationFetch, DocumentationJob, Fetched, LOADING_TEXT};
+use crate::explain::{ExplainedCommand, Explainer};
+use crate::examples::{extract_examples, ExampleTarget, PlaceholderForm, EXAMPLE_TARGET_KEY};
+use crate::keymap::Keymap;
+use crate::highlight::{Highlighter, DEFAULT_THEME, THEME_KEY};
+use crate::manpage::{get_manpage, next_match};
+use crate::options::{parse_options, parse_positionals, ManOption};
+use crate::shell::{Discovery, EntryKind, ShellEntry, ShellKind, SHELL_KEY};
+use crate::sandbox::{OutputStream, RunRecord, RunSettings, RunningCommand, RUN_SETTINGS_KEY};
+use crate::search_history::SearchSuggestion;
+use crate::path_watch::PathWatcher;
+use crate::path_check::{self, PathReport};
+use crate::package::{PackageInfo, PackageLookup};
+use crate::inventory::{self, CatalogDiff, CatalogProgress};
+use crate::variants::{self, AlternativeLinks, Variant, VariantGroups};
+use crate::whats_new::{self, Change, NewEntry, Snapshot, SNAPSHOT_FILE};
+use crate::version::{binary_mtime, is_probe_allowed, CachedVersion, VersionProbe, VersionRequest, VERSION_CACHE_FILE};
+use crate::session::{self, Session};
+use crate::subcommands::{self, SubcommandTree};
+use crate::tabs::ManpageTab;
 use eframe::egui;
-use egui::{text::LayoutJob, FontId, TextFormat};
-use syntect::easy::HighlightLines;
-use syntect::highlighting::{ThemeSet, Style};
-use syntect::parsing::SyntaxSet;
-use syntect::util::LinesWithEndings;
-use log::error;
-use webbrowser;
-use urlencoding;
+use log::{debug, error};
 use serde::{Serialize, Deserialize};
-use std::fs;
-use dirs;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const COMMAND_HISTORY_FILE: &str = "cli_organizer_history.json";
+const RESCAN_DELAY: Duration = Duration::from_millis(500);
+const CHANGE_HIGHLIGHT: Duration = Duration::from_secs(5);
+
+#[derive(Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Pane {
+    #[default]
+    CommandList,
+    Manpage,
+}
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct MyApp {
+    // Alles, was die Shell kennt; `commands` ist davon der Teil, den die Filterregeln übrig lassen
+    #[serde(skip)]
+    pub all_commands: Vec<String>,
     pub commands: Vec<String>,
+    // Treffer unter den Befehlen der obersten Ebene; `filtered_commands` enthält zusätzlich aufgeklappte Unterbefehle
+    #[serde(skip)]
+    pub matched_commands: Vec<String>,
     pub filtered_commands: Vec<String>,
     pub selected_command: Option<String>,
     pub manpage: String,
     pub search_query: String,
+    pub last_filter_query: String,
     pub scroll_to_top: bool,
     pub scroll_to_bottom: bool,
     pub manpage_search_query: String,
     pub scroll_to_selected: bool,
     pub command_history: Vec<String>,
+    pub search_history: Vec<String>,
+    pub search_suggestion_index: Option<usize>,
+    // Vorschläge zur Suche, für die sie berechnet wurden; wird geleert, wenn sich Verlauf oder Befehle ändern
+    #[serde(skip)]
+    pub suggestion_cache: Option<(String, Vec<SearchSuggestion>)>,
+    pub manpage_scroll_offset: f32,
+    pub pending_scroll_offset: Option<f32>,
+    // Gespeicherte Auswahl (mit Scrollposition), die erst nach dem Einlesen der Aliase und Funktionen bekannt ist
+    #[serde(skip)]
+    pub pending_restore: Option<(String, f32)>,
+    pub sessions: BTreeMap<String, Session>,
+    pub session_name: String,
+    pub favorites: Vec<String>,
+    pub back_stack: Vec<String>,
+    pub forward_stack: Vec<String>,
+    pub focused_pane: Pane,
+    pub manpage_viewport_height: f32,
+    pub manpage_line_height: f32,
+    pub current_match: Option<usize>,
+    pub scroll_to_match: bool,
+    pub manpage_section: Option<String>,
+    #[serde(skip)]
+    pub manpage_sections: Vec<(String, f32)>,
+    // Alle geöffneten Manpages; der Eintrag an `active_tab` wird erst beim Wechsel aktualisiert
+    pub manpage_tabs: Vec<ManpageTab>,
+    pub active_tab: usize,
+    // Tab, der rechts neben dem aktiven angezeigt wird
+    pub split_tab: Option<usize>,
+    pub command_palette_open: bool,
+    pub command_palette_query: String,
+    pub command_palette_index: usize,
+    pub help_open: bool,
+    pub theme_name: String,
+    pub examples: Vec<String>,
+    // Klappt den Beispiel-Abschnitt im nächsten Frame auf (Tastenkürzel)
+    #[serde(skip)]
+    pub expand_examples: bool,
+    pub example_target: ExampleTarget,
+    #[serde(skip)]
+    pub placeholder_form: Option<PlaceholderForm>,
+    #[serde(skip)]
+    pub options: Vec<ManOption>,
+    pub positionals: Vec<String>,
+    #[serde(skip)]
+    pub command_builder: Option<CommandBuilder>,
+    pub explain_open: bool,
+    pub explain_input: String,
+    #[serde(skip)]
+    pub explain_result: Vec<ExplainedCommand>,
+    #[serde(skip)]
+    pub explainer: Explainer,
+    pub run_open: bool,
+    pub run_input: String,
+    pub run_settings: RunSettings,
+    #[serde(skip)]
+    pub run_output: Vec<(OutputStream, String)>,
+    #[serde(skip)]
+    pub running_command: Option<RunningCommand>,
+    pub run_needs_confirmation: bool,
+    pub run_log: HashMap<String, Vec<RunRecord>>,
+    // None: aus $SHELL erkennen
+    #[serde(skip)]
+    pub shell_override: Option<ShellKind>,
+    #[serde(skip)]
+    pub shell: ShellKind,
+    #[serde(skip)]
+    pub installed_shells: Vec<ShellKind>,
+    #[serde(skip)]
+    pub shell_entries: HashMap<String, ShellEntry>,
+    #[serde(skip)]
+    pub shell_completions: HashSet<String>,
+    #[serde(skip)]
+    pub shell_discovery: Option<Receiver<Discovery>>,
+    #[serde(skip)]
+    pub documentation_fetch: DocumentationFetch,
+    #[serde(skip)]
+    pub pending_documentation: HashSet<String>,
+    #[serde(skip)]
+    pub completion_files: HashMap<String, SpecFile>,
+    #[serde(skip)]
+    pub completion_spec: Option<CompletionSpec>,
+    pub completion_open: bool,
+    #[serde(skip)]
+    pub subcommands: SubcommandTree,
+    #[serde(skip)]
+    pub variants: VariantGroups,
+    // Inhalt von /etc/alternatives; wird mit der Befehlsliste neu eingelesen, nicht bei jedem Filtern
+    #[serde(skip)]
+    pub alternative_links: AlternativeLinks,
+    // Varianten der gewählten Gruppe mit Pfad, Ziel und Alternativen
+    #[serde(skip)]
+    pub variant_details: Vec<Variant>,
+    // Details je Elterneintrag; gilt bis zum nächsten Einlesen der Befehle
+    #[serde(skip)]
+    pub variant_details_cache: HashMap<String, Vec<Variant>>,
+    #[serde(skip)]
+    pub variant_lookups: HashMap<String, Receiver<Vec<Variant>>>,
+    #[serde(skip)]
+    pub expanded_commands: HashSet<String>,
+    #[serde(skip)]
+    pub subcommand_discovery: Option<Receiver<SubcommandTree>>,
+    #[serde(skip)]
+    pub package_lookup: PackageLookup,
+    // None: kein Paket gefunden
+    #[serde(skip)]
+    pub packages: HashMap<String, Option<PackageInfo>>,
+    #[serde(skip)]
+    pub pending_packages: HashSet<String>,
+    #[serde(skip)]
+    pub version_probe: VersionProbe,
+    #[serde(skip)]
+    pub versions: HashMap<String, CachedVersion>,
+    #[serde(skip)]
+    pub pending_versions: HashSet<String>,
+    pub inventory_open: bool,
+    pub inventory_path: String,
+    #[serde(skip)]
+    pub inventory_status: Option<String>,
+    #[serde(skip)]
+    pub catalog_build: Option<Receiver<CatalogProgress>>,
+    // Programme, die eine Live-Abfrage starten würde; gesetzt, solange die Rückfrage offen ist
+    #[serde(skip)]
+    pub catalog_probe_confirmation: Option<Vec<VersionRequest>>,
+    // (abgefragte Versionen, insgesamt abzufragen)
+    #[serde(skip)]
+    pub catalog_progress: (usize, usize),
+    pub compare_left: String,
+    pub compare_right: String,
+    #[serde(skip)]
+    pub catalog_diff: Option<CatalogDiff>,
+    #[serde(skip)]
+    pub path_watcher: Option<PathWatcher>,
+    // Änderungen kommen oft in Schüben (Paketinstallation), daher wird erst nach kurzer Ruhe neu eingelesen
+    #[serde(skip)]
+    pub path_rescan_due: Option<Instant>,
+    #[serde(skip)]
+    pub command_rescan: Option<Receiver<(Vec<String>, AlternativeLinks)>>,
+    #[serde(skip)]
+    pub command_changes: HashMap<String, (RowChange, Instant)>,
+    #[serde(skip)]
+    pub categories: CategoryIndex,
+    #[serde(skip)]
+    pub category_discovery: Option<Receiver<CategoryIndex>>,
+    // Drill-down: jede weitere Kategorie grenzt die Liste weiter ein
+    #[serde(skip)]
+    pub selected_categories: Vec<Category>,
+    // Befehle nach Filterregeln und Kategorien; None, solange keine Kategorie gewählt ist
+    #[serde(skip)]
+    pub category_commands: Option<Vec<String>>,
+    // Einträge je Kategorie innerhalb der aktuellen Auswahl, für die Anzeige im Browser
+    #[serde(skip)]
+    pub category_counts: Vec<(Category, usize)>,
+    pub whats_new_open: bool,
+    pub whats_new_days: u64,
+    #[serde(skip)]
+    pub snapshot: Option<Snapshot>,
+    #[serde(skip)]
+    pub whats_new_scan: Option<Receiver<(Snapshot, Vec<NewEntry>)>>,
+    #[serde(skip)]
+    pub whats_new: Vec<NewEntry>,
+    // Stand der letzten Prüfung; wird beim Markieren als gesehen zum neuen Schnappschuss
+    #[serde(skip)]
+    pub current_snapshot: Option<Snapshot>,
+    pub path_check_open: bool,
+    #[serde(skip)]
+    pub filter_rules: Vec<FilterRule>,
+    #[serde(skip)]
+    pub filter_stats: FilterStats,
+    // Geänderte Regeln gelten sofort, gespeichert wird erst nach dem Tippen
+    #[serde(skip)]
+    pub filter_rules_dirty: bool,
+    #[serde(skip)]
+    pub command_dirs: Option<CommandDirs>,
+    pub filter_open: bool,
+    #[serde(skip)]
+    pub path_report: Option<PathReport>,
+    #[serde(skip)]
+    pub keymap: Keymap,
+    #[serde(skip)]
+    pub highlighter: Highlighter,
 }
 
 impl MyApp {
-    pub fn new() -> Self {
-        let commands = get_cli_commands();
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let shell_override: Option<ShellKind> = cc.storage
+            .and_then(|storage| eframe::get_value(storage, SHELL_KEY))
+            .flatten();
         let mut app = Self {
-            filtered_commands: commands.clone(),
-            commands,
+            matched_commands: Vec::new(),
+            filtered_commands: Vec::new(),
+            all_commands: Vec::new(),
+            commands: Vec::new(),
             selected_command: None,
             manpage: String::new(),
             search_query: String::new(),
+            last_filter_query: String::new(),
             scroll_to_top: false,
             scroll_to_bottom: false,
             manpage_search_query: String::new(),
             scroll_to_selected: false,
             command_history: Vec::new(),
+            search_history: Vec::new(),
+            search_suggestion_index: None,
+            suggestion_cache: None,
+            manpage_scroll_offset: 0.0,
+            pending_scroll_offset: None,
+            pending_restore: None,
+            sessions: BTreeMap::new(),
+            session_name: String::new(),
+            favorites: Vec::new(),
+            back_stack: Vec::new(),
+            forward_stack: Vec::new(),
+            focused_pane: Pane::CommandList,
+            manpage_viewport_height: 0.0,
+            manpage_line_height: 0.0,
+            current_match: None,
+            scroll_to_match: false,
+            manpage_section: None,
+            manpage_sections: Vec::new(),
+            manpage_tabs: Vec::new(),
+            active_tab: 0,
+            split_tab: None,
+            command_palette_open: false,
+            command_palette_query: String::new(),
+            command_palette_index: 0,
+            help_open: false,
+            theme_name: DEFAULT_THEME.to_string(),
+            examples: Vec::new(),
+            example_target: ExampleTarget::default(),
+            expand_examples: false,
+            placeholder_form: None,
+            options: Vec::new(),
+            positionals: Vec::new(),
+            command_builder: None,
+            explain_open: false,
+            explain_input: String::new(),
+            explain_result: Vec::new(),
+            explainer: Explainer::default(),
+            run_open: false,
+            run_input: String::new(),
+            run_settings: RunSettings::default(),
+            run_output: Vec::new(),
+            running_command: None,
+            run_needs_confirmation: false,
+            run_log: HashMap::new(),
+            shell_override,
+            shell: shell_override.unwrap_or_else(ShellKind::detect),
+            installed_shells: ShellKind::ALL.into_iter().filter(|shell| shell.is_installed()).collect(),
+            shell_entries: HashMap::new(),
+            shell_completions: HashSet::new(),
+            shell_discovery: None,
+            documentation_fetch: DocumentationFetch::default(),
+            pending_documentation: HashSet::new(),
+            completion_files: HashMap::new(),
+            completion_spec: None,
+            completion_open: true,
+            subcommands: SubcommandTree::default(),
+            variants: VariantGroups::default(),
+            alternative_links: Vec::new(),
+            variant_details: Vec::new(),
+            variant_details_cache: HashMap::new(),
+            variant_lookups: HashMap::new(),
+            expanded_commands: HashSet::new(),
+            subcommand_discovery: None,
+            package_lookup: PackageLookup::default(),
+            packages: HashMap::new(),
+            pending_packages: HashSet::new(),
+            version_probe: VersionProbe::default(),
+            versions: config::load_json(VERSION_CACHE_FILE).unwrap_or_default(),
+            pending_versions: HashSet::new(),
+            inventory_open: false,
+            inventory_path: dirs::home_dir()
+                .map(|home| home.join("cli_organizer_inventory.json").to_string_lossy().to_string())
+                .unwrap_or_default(),
+            inventory_status: None,
+            catalog_build: None,
+            catalog_probe_confirmation: None,
+            catalog_progress: (0, 0),
+            compare_left: String::new(),
+            compare_right: String::new(),
+            catalog_diff: None,
+            path_watcher: PathWatcher::start(cc.egui_ctx.clone()),
+            path_rescan_due: None,
+            command_rescan: None,
+            command_changes: HashMap::new(),
+            categories: CategoryIndex::default(),
+            category_discovery: None,
+            selected_categories: Vec::new(),
+            category_commands: None,
+            category_counts: Vec::new(),
+            whats_new_open: false,
+            whats_new_days: 14,
+            snapshot: config::load_json(SNAPSHOT_FILE),
+            whats_new_scan: None,
+            whats_new: Vec::new(),
+            current_snapshot: None,
+            path_check_open: false,
+            filter_rules: config::load_json(FILTER_FILE).unwrap_or_else(command_filter::default_rules),
+            filter_stats: FilterStats::default(),
+            filter_rules_dirty: false,
+            command_dirs: None,
+            filter_open: false,
+            path_report: None,
+            keymap: Keymap::load(),
+            highlighter: Highlighter::default(),
         };
+        app.load_shell_commands();
         app.load_command_history();
+        app.load_search_history();
+        app.load_favorites();
+        app.load_run_log();
+        if let Some(storage) = cc.storage {
+            let (last_session, sessions) = session::load(storage);
+            app.sessions = sessions;
+            if let Some(theme_name) = eframe::get_value(storage, THEME_KEY) {
+                app.theme_name = theme_name;
+            }
+            if let Some(example_target) = eframe::get_value(storage, EXAMPLE_TARGET_KEY) {
+                app.example_target = example_target;
+            }
+            if let Some(run_settings) = eframe::get_value(storage, RUN_SETTINGS_KEY) {
+                app.run_settings = run_settings;
+            }
+            if let Some(last_session) = last_session {
+                app.restore_session(last_session);
+            }
+        }
+        app.ensure_tabs();
         app
     }
 
-    pub fn clear_history(&mut self) {
-        self.command_history.clear();
-        if let Some(config_dir) = dirs::config_dir() {
-            let path = config_dir.join("cli_organizer_history.json");
-            if path.exists() {
-                let _ = fs::remove_file(path);
+    pub fn current_session(&self, ctx: Option<&egui::Context>) -> Session {
+        let window_size = ctx
+            .and_then(|ctx| ctx.input(|i| i.viewport().inner_rect))
+            .map(|rect| [rect.width(), rect.height()]);
+        Session {
+            selected_command: self.selected_command.clone(),
+            search_query: self.search_query.clone(),
+            manpage_search_query: self.manpage_search_query.clone(),
+            manpage_scroll_offset: self.manpage_scroll_offset,
+            window_size,
+            tabs: self
+                .manpage_tabs
+                .iter()
+                .enumerate()
+                .map(|(index, tab)| if index == self.active_tab { self.active_tab_state() } else { tab.clone() })
+                .collect(),
+            active_tab: self.active_tab,
+            split_tab: self.split_tab,
+        }
+    }
+
+    pub fn restore_session(&mut self, session: Session) {
+        self.set_search_query(session.search_query);
+        self.pending_restore = None;
+        // Ohne Eintrag in der History: "Zurück" soll nicht zur wiederhergestellten Auswahl führen
+        if let Some(command) = session.selected_command {
+            if self.commands.contains(&command) || command.contains(' ') {
+                self.show_command(&command);
+            } else if self.shell_discovery.is_some() {
+                self.pending_restore = Some((command, session.manpage_scroll_offset));
             }
         }
-        self.save_command_history(); // Dies erstellt die Datei neu
+        self.manpage_search_query = session.manpage_search_query;
+        // Die gespeicherte Position gewinnt gegen das Scrollen an den Anfang nach dem Laden der Manpage
+        self.scroll_to_top = false;
+        self.scroll_to_bottom = false;
+        self.pending_scroll_offset = Some(session.manpage_scroll_offset);
+        self.manpage_tabs = session.tabs;
+        self.active_tab = session.active_tab;
+        self.split_tab = session.split_tab;
+        self.ensure_tabs();
+        for tab in &mut self.manpage_tabs {
+            tab.pending_scroll_offset = Some(tab.scroll_offset);
+        }
     }
 
-    pub fn filter_commands(&mut self) {
-        if self.search_query.is_empty() {
-            self.filtered_commands = self.commands.clone();
-        } else {
-            self.filtered_commands = self.commands.iter()
-                .filter(|cmd| cmd.contains(&self.search_query))
-                .cloned()
-                .collect();
+    pub fn save_named_session(&mut self, ctx: &egui::Context) {
+        let name = self.session_name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let session = self.current_session(Some(ctx));
+        self.sessions.insert(name, session);
+    }
+
+    pub fn load_named_session(&mut self, name: &str, ctx: &egui::Context) {
+        if let Some(session) = self.sessions.get(name).cloned() {
+            if let Some([width, height]) = session.window_size {
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(width, height)));
+            }
+            self.session_name = name.to_string();
+            self.restore_session(session);
         }
+    }
 
-        self.filtered_commands.sort_by_key(|cmd| cmd.len());
+    pub fn delete_named_session(&mut self, name: &str) {
+        self.sessions.remove(name);
+    }
 
-        if !self.filtered_commands.is_empty() {
-            let first_command = self.filtered_commands[0].clone();
-            self.selected_command = Some(first_command.clone());
+    pub fn clear_history(&mut self) {
+        self.command_history.clear();
+        self.save_command_history();
+    }
+
+    pub fn filter_commands(&mut self) {
+        let commands = self.category_commands.as_ref().unwrap_or(&self.commands);
+        command_list::filter(commands, &mut self.matched_commands, Some(&self.last_filter_query), &self.search_query);
+        self.filtered_commands = command_list::with_children(
+            &self.matched_commands,
+            &self.subcommands.children,
+            &self.variants.children,
+            &self.variants.parents,
+            &self.expanded_commands,
+            &self.search_query,
+        );
+        self.last_filter_query.clone_from(&self.search_query);
+
+        if let Some(first_command) = self.filtered_commands.first() {
+            // Beim Eingrenzen bleibt der erste Treffer oft gleich, dann wird die Manpage nicht neu geladen
+            if self.selected_command.as_ref() != Some(first_command) {
+                let first_command = first_command.clone();
+                self.selected_command = Some(first_command.clone());
+                self.update_manpage(&first_command);
+            }
             self.scroll_to_selected = true;
-            self.update_manpage(&first_command);
         }
     }
 
+    // Setzt die Suche, ohne den ersten Treffer auszuwählen; danach folgt eine gezielte Auswahl
+    pub fn set_search_query(&mut self, query: String) {
+        self.search_query = query;
+        self.refilter_commands();
+    }
+
+    // Gezielte Auswahl (Klick, Historie, Favoriten) landet im Zurück-Verlauf
     pub fn select_command(&mut self, command: &str) {
+        if let Some(previous) = &self.selected_command {
+            if previous != command {
+                self.back_stack.push(previous.clone());
+                self.forward_stack.clear();
+            }
+        }
+        self.show_command(command);
+    }
+
+    fn show_command(&mut self, command: &str) {
         self.selected_command = Some(command.to_string());
         self.update_manpage(command);
         // Die Methode update_manpage kümmert sich jetzt um das Hinzufügen zur History
         self.scroll_to_selected = true;
     }
 
+    pub fn go_back(&mut self) {
+        if let Some(command) = self.back_stack.pop() {
+            if let Some(current) = self.selected_command.clone() {
+                self.forward_stack.push(current);
+            }
+            self.show_command(&command);
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        if let Some(command) = self.forward_stack.pop() {
+            if let Some(current) = self.selected_command.clone() {
+                self.back_stack.push(current);
+            }
+            self.show_command(&command);
+        }
+    }
+
     pub fn select_next(&mut self) {
         if let Some(selected) = &self.selected_command {
             if let Some(index) = self.filtered_commands.iter().position(|cmd| cmd == selected) {
                 if index + 1 < self.filtered_commands.len() {
                     let next_command = self.filtered_commands[index + 1].clone();
-                    self.select_command(&next_command);
+                    self.show_command(&next_command);
                 }
             }
         } else if !self.filtered_commands.is_empty() {
             let first_command = self.filtered_commands[0].clone();
-            self.select_command(&first_command);
+            self.show_command(&first_command);
         }
     }
 
@@ -103,20 +555,653 @@ impl MyApp {
             if let Some(index) = self.filtered_commands.iter().position(|cmd| cmd == selected) {
                 if index > 0 {
                     let prev_command = self.filtered_commands[index - 1].clone();
-                    self.select_command(&prev_command);
+                    self.show_command(&prev_command);
                 }
             }
         } else if !self.filtered_commands.is_empty() {
             let last_command = self.filtered_commands.last().unwrap().clone();
-            self.select_command(&last_command);
+            self.show_command(&last_command);
+        }
+    }
+
+    // Lädt Befehle, Builtins und (im Hintergrund) Aliase, Funktionen und Vervollständigungen der aktiven Shell
+    fn load_shell_commands(&mut self) {
+        let backend = self.shell.backend();
+        self.all_commands = get_cli_commands(backend);
+        self.all_commands.sort_by_key(|cmd| cmd.len());
+        self.alternative_links = variants::alternative_links();
+        self.variant_details_cache.clear();
+        self.command_dirs = None;
+        self.completion_files = completion::spec_files(&self.all_commands);
+        self.shell_entries.clear();
+        self.shell_completions.clear();
+        match backend.builtins() {
+            Ok(names) => {
+                let builtins = names
+                    .iter()
+                    .map(|name| ShellEntry::new(name, EntryKind::Builtin, self.shell, None))
+                    .collect();
+                self.merge_shell_entries(builtins);
+            }
+            Err(e) => error!("Failed to list {} builtins: {}", self.shell.name(), e),
+        }
+        self.apply_command_filter();
+        self.start_shell_discovery();
+        self.start_subcommand_discovery();
+        self.start_category_discovery();
+    }
+
+    pub fn set_shell(&mut self, shell_override: Option<ShellKind>) {
+        self.shell_override = shell_override;
+        let shell = shell_override.unwrap_or_else(ShellKind::detect);
+        if shell == self.shell {
+            return;
+        }
+        self.shell = shell;
+        self.load_shell_commands();
+        if let Some(command) = self.selected_command.clone() {
+            self.update_manpage(&command);
+        }
+    }
+
+    // Wendet die Filterregeln auf alle Befehle an; die Suche bleibt dabei erhalten
+    pub fn apply_command_filter(&mut self) {
+        let shell_entries = &self.shell_entries;
+        let result = command_filter::apply(
+            &self.filter_rules,
+            &self.all_commands,
+            |name| CommandKind::of(shell_entries.get(name).map(|entry| entry.kind)),
+            &mut self.command_dirs,
+        );
+        self.commands = result.visible;
+        self.suggestion_cache = None;
+        self.filter_stats = result.stats;
+        // Nur Programme haben Varianten; Aliase und Builtins bleiben für sich
+        let programs: Vec<String> = self.commands
+            .iter()
+            .filter(|command| !self.shell_entries.contains_key(*command))
+            .cloned()
+            .collect();
+        self.variants = variants::group(&programs, &self.alternative_links);
+        self.apply_categories();
+    }
+
+    // Grenzt die gefilterten Befehle auf die gewählten Kategorien ein und zählt, was jede weitere Kategorie übrig ließe
+    pub fn apply_categories(&mut self) {
+        self.category_commands = (!self.selected_categories.is_empty()).then(|| {
+            self.commands
+                .iter()
+                .filter(|command| self.categories.matches(command, &self.selected_categories))
+                .cloned()
+                .collect()
+        });
+        let current = self.category_commands.as_ref().unwrap_or(&self.commands);
+        self.category_counts = self.categories
+            .members
+            .iter()
+            .filter(|(category, _)| !self.selected_categories.contains(category))
+            .map(|(category, members)| (category.clone(), current.iter().filter(|command| members.contains(*command)).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        self.refilter_commands();
+    }
+
+    pub fn toggle_category(&mut self, category: Category) {
+        if let Some(index) = self.selected_categories.iter().position(|selected| *selected == category) {
+            self.selected_categories.remove(index);
+        } else {
+            self.selected_categories.push(category);
+        }
+        self.apply_categories();
+    }
+
+    fn start_category_discovery(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let commands = self.all_commands.clone();
+        thread::spawn(move || {
+            let _ = sender.send(categories::build(&commands));
+        });
+        self.category_discovery = Some(receiver);
+    }
+
+    pub fn poll_category_discovery(&mut self) {
+        let Some(receiver) = &self.category_discovery else {
+            return;
+        };
+        if let Ok(index) = receiver.try_recv() {
+            self.category_discovery = None;
+            self.categories = index;
+            self.apply_categories();
+        }
+    }
+
+    pub fn set_filter_rules(&mut self, rules: Vec<FilterRule>) {
+        self.filter_rules = rules;
+        self.filter_rules_dirty = true;
+        self.apply_command_filter();
+    }
+
+    pub fn save_filter_rules(&mut self) {
+        if self.filter_rules_dirty {
+            config::save_json(FILTER_FILE, &self.filter_rules);
+            self.filter_rules_dirty = false;
+        }
+    }
+
+    // Nach Änderungen an `commands` oder am Baum komplett neu filtern, ohne die Auswahl zu ändern
+    fn refilter_commands(&mut self) {
+        let commands = self.category_commands.as_ref().unwrap_or(&self.commands);
+        command_list::filter(commands, &mut self.matched_commands, None, &self.search_query);
+        self.filtered_commands = command_list::with_children(
+            &self.matched_commands,
+            &self.subcommands.children,
+            &self.variants.children,
+            &self.variants.parents,
+            &self.expanded_commands,
+            &self.search_query,
+        );
+        self.last_filter_query.clone_from(&self.search_query);
+    }
+
+    // Für einen Elterneintrag und jede seiner Varianten die ganze Gruppe, beim ersten Mal im Hintergrund
+    fn load_variant_details(&mut self, command: &str) {
+        let parent = self.variants.parents.get(command).cloned().unwrap_or_else(|| command.to_string());
+        self.variant_details = self.variant_details_cache.get(&parent).cloned().unwrap_or_default();
+        if self.variant_details_cache.contains_key(&parent) || self.variant_lookups.contains_key(&parent) {
+            return;
+        }
+        let Some(members) = self.variants.children.get(&parent).cloned() else {
+            return;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let group = parent.clone();
+        thread::spawn(move || {
+            let _ = sender.send(variants::details(&group, &members));
+        });
+        self.variant_lookups.insert(parent, receiver);
+    }
+
+    pub fn poll_variant_lookups(&mut self) {
+        let finished: Vec<(String, Vec<Variant>)> = self.variant_lookups
+            .iter()
+            .filter_map(|(parent, receiver)| receiver.try_recv().ok().map(|details| (parent.clone(), details)))
+            .collect();
+        for (parent, details) in finished {
+            self.variant_lookups.remove(&parent);
+            let selected = self.selected_command.as_deref().unwrap_or_default();
+            if selected == parent || self.variants.parents.get(selected) == Some(&parent) {
+                self.variant_details = details.clone();
+            }
+            self.variant_details_cache.insert(parent, details);
+        }
+    }
+
+    // Klappt Unterbefehle und Varianten gemeinsam auf bzw. zu
+    pub fn toggle_subcommands(&mut self, command: &str) {
+        if !self.expanded_commands.remove(command) {
+            self.expanded_commands.insert(command.to_string());
+        }
+        self.refilter_commands();
+    }
+
+    fn start_subcommand_discovery(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let commands = self.all_commands.clone();
+        let completion_files = self.completion_files.clone();
+        thread::spawn(move || {
+            let _ = sender.send(subcommands::discover(&commands, &completion_files));
+        });
+        self.subcommand_discovery = Some(receiver);
+    }
+
+    pub fn poll_subcommand_discovery(&mut self) {
+        let Some(receiver) = &self.subcommand_discovery else {
+            return;
+        };
+        if let Ok(tree) = receiver.try_recv() {
+            self.subcommand_discovery = None;
+            self.subcommands = tree;
+            self.refilter_commands();
+        }
+    }
+
+    // Unterbefehle gehören zum Paket ihres Werkzeugs
+    pub fn request_package(&mut self, command: &str) {
+        let program = command.split(' ').next().unwrap_or(command);
+        if self.packages.contains_key(program) || !self.pending_packages.insert(program.to_string()) {
+            return;
+        }
+        self.package_lookup.request(program);
+    }
+
+    pub fn poll_package_lookups(&mut self) {
+        while let Ok((command, info)) = self.package_lookup.results.try_recv() {
+            self.pending_packages.remove(&command);
+            self.packages.insert(command, info);
+        }
+    }
+
+    // Startet das Programm mit --version o. Ä.; nur auf ausdrücklichen Wunsch ("Version ermitteln")
+    pub fn request_version(&mut self, command: &str) {
+        let program = command.split(' ').next().unwrap_or(command);
+        if !is_probe_allowed(program) || self.pending_versions.contains(program) {
+            return;
+        }
+        let Some(path) = command_path(program) else {
+            return;
+        };
+        let mtime = binary_mtime(&path).unwrap_or(0);
+        if self.versions.get(program).is_some_and(|cached| cached.path == path && cached.mtime == mtime) {
+            return;
+        }
+        let has_version_subcommand = self.subcommands
+            .children
+            .get(program)
+            .is_some_and(|children| children.iter().any(|child| child == "version"));
+        self.pending_versions.insert(program.to_string());
+        self.version_probe.request(VersionRequest { command: program.to_string(), path, has_version_subcommand });
+    }
+
+    // Ein Ergebnis aus dem Cache gilt nur, solange Pfad und Änderungszeit der Datei gleich sind
+    fn drop_stale_version(&mut self, command: &str) {
+        let program = command.split(' ').next().unwrap_or(command);
+        let Some(cached) = self.versions.get(program) else {
+            return;
+        };
+        let fresh = command_path(program)
+            .is_some_and(|path| path == cached.path && binary_mtime(&path).unwrap_or(0) == cached.mtime);
+        if !fresh {
+            self.versions.remove(program);
+            config::save_json(VERSION_CACHE_FILE, &self.versions);
+        }
+    }
+
+    pub fn poll_version_probes(&mut self) {
+        let mut changed = false;
+        while let Ok((command, cached)) = self.version_probe.results.try_recv() {
+            self.pending_versions.remove(&command);
+            self.versions.insert(command, cached);
+            changed = true;
+        }
+        if changed {
+            config::save_json(VERSION_CACHE_FILE, &self.versions);
+        }
+    }
+
+    fn version_subcommands(&self) -> HashSet<String> {
+        self.subcommands
+            .children
+            .iter()
+            .filter(|(_, children)| children.iter().any(|child| child == "version"))
+            .map(|(parent, _)| parent.clone())
+            .collect()
+    }
+
+    // Erst zählen, dann nachfragen: eine Live-Abfrage startet jedes dieser Programme
+    pub fn prepare_version_probes(&mut self) {
+        let candidates = inventory::probe_candidates(&self.all_commands, &self.versions, &self.version_subcommands());
+        self.catalog_probe_confirmation = Some(candidates);
+    }
+
+    // Pfade und Pakete für alle Befehle sammeln dauert und läuft deshalb im Hintergrund.
+    // Programme werden nur gestartet, wenn `probe` die bestätigte Liste aus `prepare_version_probes` enthält.
+    pub fn start_catalog_export(&mut self, probe: Vec<VersionRequest>) {
+        if self.catalog_build.is_some() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let commands: Vec<String> = self.all_commands.clone();
+        let versions = self.versions.clone();
+        thread::spawn(move || {
+            let (catalog, versions) = inventory::build_catalog(&commands, versions, probe, &sender);
+            let _ = sender.send(CatalogProgress::Done(catalog, versions));
+        });
+        self.catalog_progress = (0, 0);
+        self.inventory_status = None;
+        self.catalog_build = Some(receiver);
+    }
+
+    pub fn poll_catalog_export(&mut self) {
+        let Some(receiver) = &self.catalog_build else {
+            return;
+        };
+        while let Ok(progress) = receiver.try_recv() {
+            match progress {
+                CatalogProgress::Step(done, total) => self.catalog_progress = (done, total),
+                CatalogProgress::Done(catalog, versions) => {
+                    self.catalog_build = None;
+                    self.versions = versions;
+                    config::save_json(VERSION_CACHE_FILE, &self.versions);
+                    let path = Path::new(&self.inventory_path);
+                    self.inventory_status = Some(match inventory::export(path, &catalog) {
+                        Ok(()) => format!("{} Befehle nach {} geschrieben", catalog.entries.len(), path.display()),
+                        Err(e) => format!("Export fehlgeschlagen: {}", e),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn compare_catalogs(&mut self) {
+        let left = inventory::load(Path::new(&self.compare_left));
+        let right = inventory::load(Path::new(&self.compare_right));
+        match (left, right) {
+            (Ok(left), Ok(right)) => {
+                self.catalog_diff = Some(inventory::diff(&left, &right));
+                self.inventory_status = None;
+            }
+            (Err(e), _) => self.inventory_status = Some(format!("{} konnte nicht gelesen werden: {}", self.compare_left, e)),
+            (_, Err(e)) => self.inventory_status = Some(format!("{} konnte nicht gelesen werden: {}", self.compare_right, e)),
+        }
+    }
+
+    pub fn poll_path_changes(&mut self) {
+        if let Some(watcher) = &self.path_watcher {
+            if watcher.changes.try_iter().count() > 0 {
+                self.path_rescan_due = Some(Instant::now() + RESCAN_DELAY);
+            }
+        }
+        if self.path_rescan_due.is_some_and(|due| Instant::now() >= due) && self.command_rescan.is_none() {
+            self.path_rescan_due = None;
+            let (sender, receiver) = mpsc::channel();
+            let shell = self.shell;
+            thread::spawn(move || {
+                let _ = sender.send((get_cli_commands(shell.backend()), variants::alternative_links()));
+            });
+            self.command_rescan = Some(receiver);
+        }
+        if let Some((listed, links)) = self.command_rescan.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            self.command_rescan = None;
+            self.variant_details_cache.clear();
+            self.command_dirs = None;
+            // Eine Paketinstallation kann Alternativen umbiegen, ohne dass neue Befehle dazukommen
+            if links != self.alternative_links {
+                self.alternative_links = links;
+                self.apply_command_filter();
+            }
+            self.apply_rescan(listed);
+        }
+        self.expire_command_changes();
+    }
+
+    // Neue Befehle kommen sofort dazu, entfernte bleiben noch kurz durchgestrichen stehen.
+    // Suche und Auswahl bleiben erhalten.
+    fn apply_rescan(&mut self, listed: Vec<String>) {
+        let listed: HashSet<String> = listed.into_iter().collect();
+        let known: HashSet<&String> = self.all_commands.iter().collect();
+        let added: Vec<String> = listed.iter().filter(|command| !known.contains(command)).cloned().collect();
+        let removed: Vec<String> = self.all_commands
+            .iter()
+            .filter(|command| !listed.contains(*command) && !self.shell_entries.contains_key(*command))
+            .cloned()
+            .collect();
+        if added.is_empty() && removed.is_empty() {
+            return;
+        }
+        debug!("PATH changed: {} added, {} removed", added.len(), removed.len());
+        let now = Instant::now();
+        for command in added {
+            // Eine neu installierte Fassung kann aus einem anderen Paket stammen
+            self.packages.remove(&command);
+            self.command_changes.insert(command.clone(), (RowChange::Added, now));
+            self.all_commands.push(command);
+        }
+        for command in removed {
+            self.command_changes.insert(command, (RowChange::Removed, now));
+        }
+        self.all_commands.sort_by_key(|cmd| cmd.len());
+        self.apply_command_filter();
+    }
+
+    fn expire_command_changes(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<(String, RowChange)> = self.command_changes
+            .iter()
+            .filter(|(_, (_, since))| now.duration_since(*since) >= CHANGE_HIGHLIGHT)
+            .map(|(command, (change, _))| (command.clone(), *change))
+            .collect();
+        if expired.is_empty() {
+            return;
+        }
+        let mut removed_any = false;
+        for (command, change) in expired {
+            self.command_changes.remove(&command);
+            if change == RowChange::Removed {
+                self.all_commands.retain(|existing| existing != &command);
+                removed_any = true;
+            }
+        }
+        if removed_any {
+            self.apply_command_filter();
+        }
+    }
+
+    pub fn toggle_whats_new(&mut self) {
+        self.whats_new_open = !self.whats_new_open;
+        if self.whats_new_open {
+            self.start_whats_new_scan();
+        }
+    }
+
+    // Nur Programme aus PATH, Aliase und Builtins haben keine Datei
+    pub fn start_whats_new_scan(&mut self) {
+        if self.whats_new_scan.is_some() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let commands: Vec<String> = self.all_commands
+            .iter()
+            .filter(|command| !self.shell_entries.contains_key(*command))
+            .cloned()
+            .collect();
+        let previous = self.snapshot.clone();
+        let days = self.whats_new_days;
+        thread::spawn(move || {
+            let _ = sender.send(whats_new::scan(&commands, previous.as_ref(), days));
+        });
+        self.whats_new_scan = Some(receiver);
+    }
+
+    pub fn poll_whats_new_scan(&mut self) {
+        let Some(receiver) = &self.whats_new_scan else {
+            return;
+        };
+        if let Ok((current, entries)) = receiver.try_recv() {
+            self.whats_new_scan = None;
+            self.whats_new = entries;
+            // Beim ersten Start gibt es noch nichts zu vergleichen; der heutige Stand ist der Ausgangspunkt
+            if self.snapshot.is_none() {
+                config::save_json(SNAPSHOT_FILE, &current);
+                self.snapshot = Some(current.clone());
+            }
+            self.current_snapshot = Some(current);
+        }
+    }
+
+    // Neue und geänderte Befehle gelten danach als bekannt; kürzlich geänderte Dateien bleiben in der Liste
+    pub fn mark_whats_new_seen(&mut self) {
+        let Some(current) = self.current_snapshot.clone() else {
+            return;
+        };
+        config::save_json(SNAPSHOT_FILE, &current);
+        self.snapshot = Some(current);
+        self.whats_new.retain(|entry| entry.change == Change::Recent);
+    }
+
+    // Beim Öffnen neu prüfen, PATH oder Dateien könnten sich seitdem geändert haben
+    pub fn toggle_path_check(&mut self) {
+        self.path_check_open = !self.path_check_open;
+        if self.path_check_open {
+            self.path_report = Some(path_check::analyze());
+        }
+    }
+
+    // Solange im Hintergrund noch etwas läuft, regelmäßig neu zeichnen, damit Ergebnisse sichtbar werden
+    pub fn background_work_pending(&self) -> bool {
+        self.shell_discovery.is_some()
+            || !self.pending_documentation.is_empty()
+            || self.subcommand_discovery.is_some()
+            || !self.variant_lookups.is_empty()
+            || !self.pending_packages.is_empty()
+            || !self.pending_versions.is_empty()
+            || self.catalog_build.is_some()
+            || self.category_discovery.is_some()
+            || self.whats_new_scan.is_some()
+            || self.path_rescan_due.is_some()
+            || self.command_rescan.is_some()
+            || !self.command_changes.is_empty()
+            || self.explainer.is_loading()
+    }
+
+    fn start_shell_discovery(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let shell = self.shell;
+        thread::spawn(move || {
+            let _ = sender.send(shell.discover());
+        });
+        self.shell_discovery = Some(receiver);
+    }
+
+    // Übernimmt Aliase, Funktionen und Vervollständigungen, sobald die Login-Shell geantwortet hat
+    pub fn poll_shell_discovery(&mut self) {
+        let Some(receiver) = &self.shell_discovery else {
+            return;
+        };
+        if let Ok(discovery) = receiver.try_recv() {
+            self.shell_discovery = None;
+            self.shell_completions = discovery.completions.into_iter().collect();
+            self.merge_shell_entries(discovery.entries);
+            self.finish_restore();
+        }
+    }
+
+    // Holt die Auswahl nach, sofern der Benutzer inzwischen nichts anderes gewählt hat
+    fn finish_restore(&mut self) {
+        let Some((command, offset)) = self.pending_restore.take() else {
+            return;
+        };
+        if self.selected_command.is_none() && self.commands.contains(&command) {
+            self.show_command(&command);
+            self.scroll_to_top = false;
+            self.pending_scroll_offset = Some(offset);
+        }
+    }
+
+    fn merge_shell_entries(&mut self, entries: Vec<ShellEntry>) {
+        let mut changed = false;
+        for entry in entries {
+            if !is_command_name(&entry.name) {
+                continue;
+            }
+            if !self.all_commands.contains(&entry.name) {
+                self.all_commands.push(entry.name.clone());
+            }
+            // Auch bekannte Namen können jetzt eine andere Art haben, z. B. ein Alias statt eines Programms
+            changed = true;
+            // Ein Alias verdeckt ein gleichnamiges Builtin, ein Builtin keinen Alias
+            let keep_existing = self.shell_entries
+                .get(&entry.name)
+                .is_some_and(|existing| existing.kind != EntryKind::Builtin && entry.kind == EntryKind::Builtin);
+            if !keep_existing {
+                self.shell_entries.insert(entry.name.clone(), entry);
+            }
+        }
+        if changed {
+            self.all_commands.sort_by_key(|cmd| cmd.len());
+            self.apply_command_filter();
+        }
+    }
+
+    // Manpage für Programme, `help` bzw. Shell-Äquivalente für Builtins, Definitionen für Aliase und Funktionen.
+    // None: die Dokumentation wird im Hintergrund geholt und kommt über `poll_documentation`.
+    fn fetch_documentation(&mut self, command: &str) -> Option<io::Result<String>> {
+        if let Some((parent, sub)) = command.split_once(' ') {
+            let job = DocumentationJob::Subcommand {
+                parent: parent.to_string(),
+                sub: sub.to_string(),
+                description: self.subcommands.descriptions.get(command).cloned(),
+            };
+            self.request_documentation(command, job);
+            return None;
+        }
+        let Some(entry) = self.shell_entries.get(command).cloned() else {
+            return Some(get_manpage(command));
+        };
+        match entry.kind {
+            EntryKind::Builtin => {
+                self.request_documentation(command, DocumentationJob::Builtin(entry));
+                None
+            }
+            EntryKind::Alias | EntryKind::Function => {
+                self.request_documentation(command, DocumentationJob::Definition(entry));
+                None
+            }
+        }
+    }
+
+    fn request_documentation(&mut self, command: &str, job: DocumentationJob) {
+        if self.pending_documentation.insert(command.to_string()) {
+            self.documentation_fetch.request(command, job);
+        }
+    }
+
+    // Übernimmt im Hintergrund geholte Dokumentation, wenn der Befehl noch gewählt oder in einem Tab offen ist
+    pub fn poll_documentation(&mut self) {
+        while let Ok(Fetched { command, job, text }) = self.documentation_fetch.results.try_recv() {
+            self.pending_documentation.remove(&command);
+            let documentation = match job {
+                DocumentationJob::Definition(entry) => {
+                    // Nur einmal bei der Shell nachfragen
+                    if let Some(stored) = self.shell_entries.get_mut(&command).filter(|stored| stored.definition.is_none()) {
+                        stored.definition = entry.definition;
+                    }
+                    text
+                }
+                DocumentationJob::Builtin(_) | DocumentationJob::Subcommand { .. } => text,
+            };
+            for (index, tab) in self.manpage_tabs.iter_mut().enumerate() {
+                if index != self.active_tab && tab.command == command && tab.manpage == LOADING_TEXT {
+                    tab.manpage = match &documentation {
+                        Ok(manpage) if !manpage.is_empty() => manpage.clone(),
+                        _ => String::from("Nicht verfügbar"),
+                    };
+                }
+            }
+            if self.selected_command.as_deref() == Some(command.as_str()) && self.manpage == LOADING_TEXT {
+                self.show_documentation(&command, documentation);
+            }
         }
     }
 
     pub fn update_manpage(&mut self, command: &str) {
-        match get_manpage(command) {
+        match self.fetch_documentation(command) {
+            Some(documentation) => self.show_documentation(command, documentation),
+            None => self.show_pending_documentation(command),
+        }
+    }
+
+    // Paket, Version usw. gleich laden, die Seite selbst kommt später; der Platzhalter landet nicht in der History
+    fn show_pending_documentation(&mut self, command: &str) {
+        self.show_documentation(command, Ok(String::new()));
+        self.manpage = String::from(LOADING_TEXT);
+    }
+
+    fn show_documentation(&mut self, command: &str, documentation: io::Result<String>) {
+        self.request_package(command);
+        self.drop_stale_version(command);
+        self.load_variant_details(command);
+        self.completion_spec = self.completion_files
+            .get(command)
+            .and_then(|file| completion::load(command, file));
+        match documentation {
             Ok(manpage) => {
                 if !manpage.is_empty() {
                     self.manpage = manpage;
+                    self.examples = extract_examples(&self.manpage);
+                    self.options = parse_options(&self.manpage);
+                    self.positionals = parse_positionals(&self.manpage);
+                    self.current_match = None;
                     self.scroll_to_top = true;
                     self.scroll_to_bottom = false;
                     // Füge den Befehl zur History hinzu, nur wenn eine Manpage verfügbar ist
@@ -126,209 +1211,203 @@ impl MyApp {
                     }
                 } else {
                     self.manpage = String::from("Nicht verfügbar");
+                    self.examples.clear();
+                    self.options.clear();
+                    self.positionals.clear();
                     // Hier wird der Befehl nicht zur History hinzugefügt
                 }
             },
             Err(e) => {
                 error!("Failed to fetch manpage: {}", e);
                 self.manpage = String::from("Nicht verfügbar");
+                self.examples.clear();
+                self.options.clear();
+                self.positionals.clear();
                 // Hier wird der Befehl ebenfalls nicht zur History hinzugefügt
             },
         }
     }
 
-    pub fn filter_manpage(&self) -> String {
+    pub fn scroll_manpage_by(&mut self, delta: f32) {
+        let offset = self.pending_scroll_offset.unwrap_or(self.manpage_scroll_offset);
+        self.pending_scroll_offset = Some((offset + delta).max(0.0));
+    }
+
+    // Springt zur nächsten bzw. vorherigen Zeile der gefilterten Manpage, die den Suchbegriff enthält
+    pub fn jump_to_match(&mut self, direction: isize) {
         if self.manpage_search_query.is_empty() {
-            return self.manpage.clone();
+            return;
         }
+        self.current_match = next_match(&self.manpage, &self.manpage_search_query, self.current_match, direction);
+        self.scroll_to_match = self.current_match.is_some();
+    }
 
-        self.manpage
-            .lines()
-            .filter(|line| line.contains(&self.manpage_search_query))
-            .collect::<Vec<_>>()
-            .join("\n")
+    // Mindestens ein Tab, und die Indizes zeigen auf vorhandene, verschiedene Tabs
+    fn ensure_tabs(&mut self) {
+        if self.manpage_tabs.is_empty() {
+            self.manpage_tabs.push(self.active_tab_state());
+        }
+        if self.active_tab >= self.manpage_tabs.len() {
+            self.active_tab = 0;
+        }
+        self.split_tab = self.split_tab.filter(|index| *index != self.active_tab && *index < self.manpage_tabs.len());
     }
 
-    pub fn open_perplexity_search(&self) {
-        if let Some(command) = &self.selected_command {
-            let query = format!("{} show me example usages for this command", command);
-            let url = format!("https://www.perplexity.ai/search?q={}", urlencoding::encode(&query));
-            if webbrowser::open(&url).is_err() {
-                error!("Failed to open web browser");
-            }
+    fn active_tab_state(&self) -> ManpageTab {
+        ManpageTab {
+            command: self.selected_command.clone().unwrap_or_default(),
+            manpage: self.manpage.clone(),
+            search_query: self.manpage_search_query.clone(),
+            scroll_offset: self.manpage_scroll_offset,
+            current_match: self.current_match,
+            section: self.manpage_section.clone(),
+            sections: self.manpage_sections.clone(),
+            pending_scroll_offset: Some(self.manpage_scroll_offset),
+            scroll_to_match: false,
         }
     }
 
-    fn load_command_history(&mut self) {
-        if let Some(config_dir) = dirs::config_dir() {
-            let path = config_dir.join("cli_organizer_history.json");
-            if let Ok(contents) = fs::read_to_string(path) {
-                if let Ok(history) = serde_json::from_str(&contents) {
-                    self.command_history = history;
-                }
+    fn store_active_tab(&mut self) {
+        let state = self.active_tab_state();
+        match self.manpage_tabs.get_mut(self.active_tab) {
+            Some(tab) => *tab = state,
+            None => {
+                self.active_tab = self.manpage_tabs.len();
+                self.manpage_tabs.push(state);
             }
         }
     }
 
-    fn save_command_history(&self) {
-        if let Some(config_dir) = dirs::config_dir() {
-            let path = config_dir.join("cli_organizer_history.json");
-            if let Ok(json) = serde_json::to_string(&self.command_history) {
-                let _ = fs::write(path, json);
+    // Ein Wechsel auf den Tab der geteilten Ansicht tauscht die beiden Seiten
+    pub fn activate_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.manpage_tabs.len() {
+            return;
+        }
+        self.store_active_tab();
+        if self.split_tab == Some(index) {
+            self.split_tab = Some(self.active_tab);
+        }
+        self.active_tab = index;
+        let tab = self.manpage_tabs[index].clone();
+        if tab.command.is_empty() {
+            self.selected_command = None;
+            self.manpage.clear();
+        } else {
+            let documentation = if tab.manpage.is_empty() || tab.manpage == LOADING_TEXT {
+                self.fetch_documentation(&tab.command)
+            } else {
+                Some(Ok(tab.manpage))
+            };
+            self.selected_command = Some(tab.command.clone());
+            match documentation {
+                Some(documentation) => self.show_documentation(&tab.command, documentation),
+                None => self.show_pending_documentation(&tab.command),
             }
         }
+        self.manpage_search_query = tab.search_query;
+        self.current_match = tab.current_match;
+        self.manpage_section = tab.section;
+        self.manpage_sections = tab.sections;
+        self.scroll_to_top = false;
+        self.pending_scroll_offset = Some(tab.scroll_offset);
+        self.scroll_to_selected = true;
     }
 
-  
-}
+    pub fn open_in_new_tab(&mut self, command: &str) {
+        self.store_active_tab();
+        self.manpage_tabs.push(ManpageTab::new(command));
+        self.active_tab = self.manpage_tabs.len() - 1;
+        self.manpage_search_query.clear();
+        self.manpage_section = None;
+        self.manpage_sections.clear();
+        self.select_command(command);
+    }
 
-impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::SidePanel::left("side_panel").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.heading("Programme:");
-                ui.label(format!("{} Befehle", self.filtered_commands.len()));
-            });
-            ui.horizontal(|ui| {
-                ui.label("Suche:");
-                if ui.text_edit_singleline(&mut self.search_query).changed() {
-                    self.filter_commands();
-                }
-            });
-            let scroll_area = egui::ScrollArea::vertical();
-            let mut selected_command = None;
-            scroll_area.show(ui, |ui| {
-                for command in self.filtered_commands.iter() {
-                    let is_selected = self.selected_command.as_ref() == Some(command);
-                    if ui.selectable_label(is_selected, command).clicked() {
-                        selected_command = Some(command.clone());
-                    }
-                    if is_selected && self.scroll_to_selected {
-                        ui.scroll_to_cursor(Some(egui::Align::Center));
-                        self.scroll_to_selected = false;
-                    }
-                }
-            });
-            if let Some(command) = selected_command {
-                self.update_manpage(&command);
-                self.selected_command = Some(command);
-            }
-        });
-        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            if let Some(command) = &self.selected_command {
-                if ui.button(format!("Example Usage for {}", command)).clicked() {
-                    self.open_perplexity_search();
-                }
-            } else {
-                ui.label("Select a command to see example usage");
-            }
-        });
+    pub fn close_tab(&mut self, index: usize) {
+        if self.manpage_tabs.len() < 2 || index >= self.manpage_tabs.len() {
+            return;
+        }
+        if index == self.active_tab {
+            self.activate_tab(if index + 1 < self.manpage_tabs.len() { index + 1 } else { index - 1 });
+        }
+        self.manpage_tabs.remove(index);
+        if self.active_tab > index {
+            self.active_tab -= 1;
+        }
+        self.split_tab = match self.split_tab {
+            Some(split) if split == index => None,
+            Some(split) if split > index => Some(split - 1),
+            split => split,
+        };
+    }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Manpage");
-            ui.horizontal(|ui| {
-                if ui.button("⬆ Scroll to Top").clicked() {
-                    self.scroll_to_top = true;
-                    self.scroll_to_bottom = false;
-                }
-                if ui.button("⬇ Scroll to Bottom").clicked() {
-                    self.scroll_to_bottom = true;
-                    self.scroll_to_top = false;
-                }
-            });
-            ui.horizontal(|ui| {
-                ui.label("Suche in Manpage:");
-                if ui.text_edit_singleline(&mut self.manpage_search_query).changed() {
-                    self.scroll_to_top = true;
-                    self.scroll_to_bottom = false;
-                }
-            });
-            let mut scroll_area = egui::ScrollArea::vertical()
-                .id_source("manpage_scroll_area")
-                .auto_shrink([false; 2]);
-            if self.scroll_to_top {
-                scroll_area = scroll_area.vertical_scroll_offset(0.0);
-                self.scroll_to_top = false;
-            }
-            scroll_area.show(ui, |ui| {
-                if self.manpage.is_empty() {
-                    ui.label("Nicht verfügbar");
-                } else {
-                    let filtered_manpage = self.filter_manpage();
-                    let syntax_set = SyntaxSet::load_defaults_newlines();
-                    let theme_set = ThemeSet::load_defaults();
-                    let syntax = syntax_set.find_syntax_plain_text();
-                    let mut h = HighlightLines::new(syntax, &theme_set.themes["base16-ocean.dark"]);
-                    let mut job = LayoutJob::default();
-                    for line in LinesWithEndings::from(&filtered_manpage) {
-                        let ranges: Vec<(Style, &str)> = h.highlight_line(line, &syntax_set).unwrap();
-                        for (style, text) in ranges {
-                            let color = egui::Color32::from_rgb(style.foreground.r, style.foreground.g, style.foreground.b);
-                            job.append(text, 0.0, TextFormat::simple(FontId::default(), color));
-                        }
-                    }
-                    ui.label(job);
-                    ui.add_space(20.0); // Fügt 20px Padding am unteren Rand hinzu
-                    if self.scroll_to_bottom {
-                        ui.scroll_to_cursor(Some(egui::Align::BOTTOM));
-                        self.scroll_to_bottom = false;
-                    }
-                }
-            });
-        });
+    pub fn cycle_tab(&mut self, direction: isize) {
+        let count = self.manpage_tabs.len() as isize;
+        if count > 1 {
+            self.activate_tab((self.active_tab as isize + direction).rem_euclid(count) as usize);
+        }
+    }
 
-        let history_height = ((self.command_history.len() as f32 / 10.0).ceil() * 30.0).max(100.0);
-
-        egui::TopBottomPanel::bottom("history_panel")
-            .resizable(false)
-            .min_height(history_height)
-            .show(ctx, |ui| {
-                egui::Frame::none()
-                    .fill(ui.style().visuals.extreme_bg_color)
-                    .show(ui, |ui| {
-                        ui.add_space(10.0);
-                        ui.horizontal(|ui| {
-                            ui.heading("Historie");
-                            if ui.button("Löschen").clicked() {
-                                self.clear_history();
-                            }
-                        });
-                        ui.add_space(5.0);
-                        
-                        let mut selected_command = None;
-                        egui::Grid::new("history_grid")
-                            .num_columns(10)
-                            .spacing([5.0, 5.0])
-                            .show(ui, |ui| {
-                                for (index, command) in self.command_history.iter().enumerate() {
-                                    if ui.button(command).clicked() {
-                                        selected_command = Some(command.clone());
-                                    }
-                                    if (index + 1) % 10 == 0 {
-                                        ui.end_row();
-                                    }
-                                }
-                            });
-
-                        if let Some(command) = selected_command {
-                            self.select_command(&command);
-                            self.search_query.clear();
-                            self.filter_commands();
-                        }
-
-                        ui.add_space(10.0);
-                    });
-            });
+    // Ohne zweiten Tab wird die aktuelle Seite verdoppelt, etwa um zwei Abschnitte nebeneinander zu lesen
+    pub fn toggle_split(&mut self) {
+        if self.split_tab.take().is_some() {
+            return;
+        }
+        self.store_active_tab();
+        if self.manpage_tabs.len() < 2 {
+            let copy = self.manpage_tabs[self.active_tab].clone();
+            self.manpage_tabs.push(copy);
+        }
+        self.show_in_split((self.active_tab + 1) % self.manpage_tabs.len());
+    }
 
-        ctx.input(|i| {
-            if i.key_pressed(egui::Key::ArrowDown) {
-                self.select_next();
+    pub fn show_in_split(&mut self, index: usize) {
+        if index == self.active_tab {
+            return;
+        }
+        if let Some(tab) = self.manpage_tabs.get_mut(index) {
+            tab.pending_scroll_offset = Some(tab.scroll_offset);
+            self.split_tab = Some(index);
+        }
+    }
+
+    // Nach einem Neustart oder für im Hintergrund geöffnete Tabs
+    pub fn load_split_manpage(&mut self) {
+        let Some(index) = self.split_tab else {
+            return;
+        };
+        let Some(command) = self.manpage_tabs.get(index).filter(|tab| tab.manpage.is_empty()).map(|tab| tab.command.clone()) else {
+            return;
+        };
+        let manpage = match self.fetch_documentation(&command) {
+            None => String::from(LOADING_TEXT),
+            Some(Ok(manpage)) if !manpage.is_empty() => manpage,
+            Some(Ok(_)) => String::from("Nicht verfügbar"),
+            Some(Err(e)) => {
+                error!("Failed to fetch manpage: {}", e);
+                String::from("Nicht verfügbar")
             }
-            if i.key_pressed(egui::Key::ArrowUp) {
-                self.select_previous();
+        };
+        self.manpage_tabs[index].manpage = manpage;
+    }
+
+    pub fn open_perplexity_search(&self) {
+        if let Some(command) = &self.selected_command {
+            let query = format!("{} show me example usages for this command", command);
+            let url = format!("https://www.perplexity.ai/search?q={}", urlencoding::encode(&query));
+            if webbrowser::open(&url).is_err() {
+                error!("Failed to open web browser");
             }
-        });
+        }
     }
-}
 
+    fn load_command_history(&mut self) {
+        if let Some(history) = config::load_json(COMMAND_HISTORY_FILE) {
+            self.command_history = history;
+        }
+    }
 
+    fn save_command_history(&self) {
+        config::save_json(COMMAND_HISTORY_FILE, &self.command_history);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::app::MyApp;
+use crate::options::ManOption;
+
+pub struct CommandBuilder {
+    pub command: String,
+    pub options: Vec<ManOption>,
+    pub selected: Vec<bool>,
+    pub values: Vec<String>,
+    pub positionals: Vec<(String, String)>,
+    pub filter: String,
+}
+
+impl CommandBuilder {
+    pub fn new(command: &str, options: &[ManOption], positionals: &[String]) -> Self {
+        Self {
+            command: command.to_string(),
+            options: options.to_vec(),
+            selected: vec![false; options.len()],
+            values: vec![String::new(); options.len()],
+            positionals: positionals.iter().map(|name| (name.clone(), String::new())).collect(),
+            filter: String::new(),
+        }
+    }
+
+    pub fn selected_options(&self) -> impl Iterator<Item = (&ManOption, &str)> {
+        self.options
+            .iter()
+            .zip(&self.selected)
+            .zip(&self.values)
+            .filter(|((_, selected), _)| **selected)
+            .map(|((option, _), value)| (option, value.as_str()))
+    }
+
+    // Bevorzugt die lange Form ("--width=80"), kurze Optionen bekommen den Wert als eigenes Argument.
+    // Ein optionaler Wert muss direkt an der kurzen Option hängen ("-O3"), sonst wäre er ein eigenes Argument.
+    pub fn preview(&self) -> String {
+        let mut parts = vec![self.command.clone()];
+        for (option, value) in self.selected_options() {
+            let flag = option.flags.iter().find(|f| f.starts_with("--")).unwrap_or(&option.flags[0]);
+            match (&option.argument, value.is_empty()) {
+                (Some(_), false) if flag.starts_with("--") => parts.push(format!("{}={}", flag, shell_quote(value))),
+                (Some(_), false) if option.optional_argument => parts.push(format!("{}{}", flag, shell_quote(value))),
+                (Some(_), false) => {
+                    parts.push(flag.clone());
+                    parts.push(shell_quote(value));
+                }
+                (Some(argument), true) if !option.optional_argument => parts.push(format!("{} <{}>", flag, argument)),
+                _ => parts.push(flag.clone()),
+            }
+        }
+        for (name, value) in &self.positionals {
+            if value.is_empty() {
+                parts.push(format!("<{}>", name));
+            } else {
+                parts.push(shell_quote(value));
+            }
+        }
+        parts.join(" ")
+    }
+}
+
+pub fn shell_quote(value: &str) -> String {
+    let is_safe = !value.is_empty()
+        && value.chars().all(|c| c.is_alphanumeric() || "_./:=@%+,-".contains(c));
+    if is_safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+impl MyApp {
+    pub fn open_command_builder(&mut self) {
+        if let Some(command) = &self.selected_command {
+            self.command_builder = Some(CommandBuilder::new(command, &self.options, &self.positionals));
+        }
+    }
+
+    pub fn save_built_command(&mut self, command_line: &str) {
+        if !self.is_favorite(command_line) {
+            self.toggle_favorite(command_line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(flags: &[&str], argument: Option<&str>, optional_argument: bool) -> ManOption {
+        ManOption {
+            flags: flags.iter().map(|flag| flag.to_string()).collect(),
+            argument: argument.map(str::to_string),
+            optional_argument,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn shell_quote_leaves_only_safe_words_bare() {
+        assert_eq!(shell_quote("file.txt"), "file.txt");
+        assert_eq!(shell_quote("--width=80"), "--width=80");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("my file"), "'my file'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("~/x"), "'~/x'");
+        assert_eq!(shell_quote("~root"), "'~root'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn preview_places_values_by_option_form() {
+        let options = [
+            option(&["-w", "--width"], Some("COLS"), false),
+            option(&["-o"], Some("FILE"), false),
+            option(&["-O"], Some("LEVEL"), true),
+            option(&["-a", "--all"], None, false),
+            option(&["-t"], Some("TYPE"), false),
+        ];
+        let mut builder = CommandBuilder::new("tool", &options, &["SOURCE".to_string(), "DEST".to_string()]);
+        builder.selected = vec![true; options.len()];
+        builder.values = ["80", "out file", "2", "", ""].map(str::to_string).to_vec();
+        builder.positionals[0].1 = "a b".to_string();
+        assert_eq!(builder.preview(), "tool --width=80 -o 'out file' -O2 --all -t <TYPE> 'a b' <DEST>");
+
+        builder.values[2].clear();
+        builder.selected = vec![false, false, true, false, false];
+        assert_eq!(builder.preview(), "tool -O 'a b' <DEST>");
+    }
+}
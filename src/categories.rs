@@ -0,0 +1,144 @@
+use crate::manpage::{installed_pages, page_descriptions};
+use crate::package::dpkg_command_sections;
+use log::debug;
+use std::collections::{BTreeMap, HashSet};
+
+// Schlagwörter für Themen, gesucht am Wortanfang in Manpage- und Paketbeschreibungen.
+// Ein Leerzeichen am Ende verlangt das ganze Wort ("ip " trifft nicht "ipc").
+const TOPICS: [(&str, &[&str]); 5] = [
+    (
+        "Netzwerk",
+        &["network", "http", "ftp", "ssh", "dns", "socket", "tcp", "udp", "ip ", "url", "download", "mail", "proxy", "wireless", "remote", "firewall", "routing"],
+    ),
+    (
+        "Dateisystem",
+        &["file system", "filesystem", "mount", "disk", "partition", "director", "inode", "fsck", "block device", "symlink", "file permission", "quota"],
+    ),
+    (
+        "Textverarbeitung",
+        &["text", "pattern", "stream editor", "regular expression", "sort", "column", "character", "spell", "diff", "markup", "encoding"],
+    ),
+    ("Archive", &["archive", "compress", "decompress", "tar ", "zip", "gzip", "bzip", "xz ", "zstd", "cpio", "extract"]),
+    (
+        "Entwicklung",
+        &["compile", "debug", "linker", "assembler", "object file", "build", "source code", "librar", "profil", "version control", "interpreter", "python", "perl", "ruby", "javascript", "llvm", "gcc", "cargo", "git"],
+    ),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CategorySource {
+    ManSection,
+    PackageSection,
+    Topic,
+}
+
+impl CategorySource {
+    pub const ALL: [CategorySource; 3] = [CategorySource::Topic, CategorySource::PackageSection, CategorySource::ManSection];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CategorySource::ManSection => "Manpage-Abschnitt",
+            CategorySource::PackageSection => "Paket-Sektion",
+            CategorySource::Topic => "Thema",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category {
+    pub source: CategorySource,
+    pub name: String,
+}
+
+impl Category {
+    pub fn label(&self) -> String {
+        match self.source {
+            CategorySource::ManSection => format!("{} ({})", self.name, man_section_title(&self.name)),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+fn man_section_title(section: &str) -> &'static str {
+    match section.chars().next() {
+        Some('1') => "Benutzerbefehle",
+        Some('2') => "Systemaufrufe",
+        Some('3') => "Bibliotheksfunktionen",
+        Some('4') => "Gerätedateien",
+        Some('5') => "Dateiformate",
+        Some('6') => "Spiele",
+        Some('7') => "Verschiedenes",
+        Some('8') => "Systemverwaltung",
+        Some('9') => "Kernel",
+        _ => "Sonstige",
+    }
+}
+
+#[derive(Default)]
+pub struct CategoryIndex {
+    pub members: BTreeMap<Category, HashSet<String>>,
+}
+
+impl CategoryIndex {
+    // Befehle, die in allen gewählten Kategorien liegen; ohne Auswahl alle
+    pub fn matches(&self, command: &str, selected: &[Category]) -> bool {
+        selected
+            .iter()
+            .all(|category| self.members.get(category).is_some_and(|members| members.contains(command)))
+    }
+}
+
+// Läuft im Hintergrund: liest alle Manpage-Verzeichnisse, die man-Datenbank und den dpkg-Status
+pub fn build(commands: &[String]) -> CategoryIndex {
+    let pages = installed_pages();
+    let descriptions = page_descriptions();
+    let packages = dpkg_command_sections();
+    let mut index = CategoryIndex::default();
+    let mut add = |source: CategorySource, name: &str, command: &str| {
+        index
+            .members
+            .entry(Category { source, name: name.to_string() })
+            .or_default()
+            .insert(command.to_string());
+    };
+    for command in commands {
+        if let Some(section) = pages.get(command) {
+            add(CategorySource::ManSection, section, command);
+        }
+        let package = packages.get(command);
+        if let Some((section, _)) = package {
+            add(CategorySource::PackageSection, section, command);
+        }
+        let mut text = String::new();
+        if let Some(description) = descriptions.get(command) {
+            text.push_str(description);
+        }
+        if let Some(summary) = package.and_then(|(_, summary)| summary.as_ref()) {
+            text.push(' ');
+            text.push_str(summary);
+        }
+        for topic in topics(&text) {
+            add(CategorySource::Topic, topic, command);
+        }
+    }
+    debug!("Built {} categories for {} commands", index.members.len(), commands.len());
+    index
+}
+
+fn topics(description: &str) -> Vec<&'static str> {
+    if description.is_empty() {
+        return Vec::new();
+    }
+    // " list directory contents " – Satzzeichen als Wortgrenzen, damit " text" auch "(text)" findet
+    let normalized: String = description
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let padded = format!(" {} ", normalized);
+    TOPICS
+        .iter()
+        .filter(|(_, keywords)| keywords.iter().any(|keyword| padded.contains(&format!(" {}", keyword))))
+        .map(|(topic, _)| *topic)
+        .collect()
+}
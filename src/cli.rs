@@ -1,22 +1,38 @@
-use std::process::Command;
-use log::debug;
+use crate::shell::ShellBackend;
+use log::{debug, error};
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
 
-pub fn get_cli_commands() -> Vec<String> {
-    let output = Command::new("bash")
-        .arg("-c")
-        .arg("compgen -c")
-        .output()
-        .expect("Failed to execute command");
-    let commands = String::from_utf8_lossy(&output.stdout);
-    let result: Vec<String> = commands
-        .lines()
-        .filter(|cmd| {
-            cmd.len() > 1 && // Filtert Befehle mit nur einem Zeichen
-            cmd.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') && // Erlaubt nur alphanumerische Zeichen, Unterstriche und Bindestriche
-            !cmd.starts_with('-') // Filtert Optionen (beginnend mit '-') aus
-        })
-        .map(|s| s.to_string())
-        .collect();
-    debug!("Fetched {} filtered CLI commands", result.len());
+pub fn get_cli_commands(backend: &dyn ShellBackend) -> Vec<String> {
+    let commands = match backend.commands() {
+        Ok(commands) => commands,
+        Err(e) => {
+            error!("Failed to list commands with {}: {}", backend.kind().name(), e);
+            return Vec::new();
+        }
+    };
+    let mut result: Vec<String> = commands.into_iter().filter(|cmd| is_command_name(cmd)).collect();
+    // zsh und fish liefern Programme, die in mehreren PATH-Verzeichnissen liegen, mehrfach
+    result.sort();
+    result.dedup();
+    debug!("Fetched {} CLI commands from {}", result.len(), backend.kind().name());
     result
 }
+
+// Nur was sich überhaupt als Befehl aufrufen lässt; alles Weitere regeln die Filter des Benutzers (command_filter)
+pub fn is_command_name(cmd: &str) -> bool {
+    !cmd.is_empty() && // Leere Zeilen der Shell-Ausgabe
+    !cmd.contains(|c: char| c.is_whitespace() || c.is_control() || c == '/') // Ein Name mit Leerzeichen oder Pfadtrenner ist kein Befehl
+}
+
+// Die Datei, die beim Aufruf von `command` ausgeführt wird: der erste ausführbare Treffer in PATH
+pub fn command_path(command: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(command))
+        .find(|candidate| {
+            candidate
+                .metadata()
+                .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        })
+}
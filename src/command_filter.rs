@@ -0,0 +1,357 @@
+use crate::shell::EntryKind;
+use log::debug;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+pub const FILTER_FILE: &str = "cli_organizer_filters.json";
+// Schlüsselwörter tauchen bei `compgen -c` zwischen den Befehlen auf
+const SHELL_KEYWORDS: &str = "^(if|then|else|elif|fi|case|esac|for|select|while|until|do|done|in|function|time|coproc)$";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RuleAction {
+    Include,
+    Exclude,
+}
+
+impl RuleAction {
+    pub const ALL: [RuleAction; 2] = [RuleAction::Include, RuleAction::Exclude];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RuleAction::Include => "Nur zeigen",
+            RuleAction::Exclude => "Ausblenden",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CommandKind {
+    Program,
+    Builtin,
+    Alias,
+    Function,
+}
+
+impl CommandKind {
+    pub const ALL: [CommandKind; 4] = [CommandKind::Program, CommandKind::Builtin, CommandKind::Alias, CommandKind::Function];
+
+    // Ohne Shell-Eintrag ist es ein Programm aus PATH
+    pub fn of(entry: Option<EntryKind>) -> Self {
+        match entry {
+            None => CommandKind::Program,
+            Some(EntryKind::Builtin) => CommandKind::Builtin,
+            Some(EntryKind::Alias) => CommandKind::Alias,
+            Some(EntryKind::Function) => CommandKind::Function,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CommandKind::Program => "Programm",
+            CommandKind::Builtin => "Builtin",
+            CommandKind::Alias => "Alias",
+            CommandKind::Function => "Funktion",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum Pattern {
+    Glob(String),
+    Regex(String),
+    // Verzeichnis, aus dem der Befehl laut PATH stammt, inklusive Unterverzeichnissen
+    Directory(String),
+    Kind(CommandKind),
+}
+
+impl Pattern {
+    pub const TYPE_LABELS: [&'static str; 4] = ["Glob", "Regex", "Verzeichnis", "Art"];
+
+    pub fn type_label(&self) -> &'static str {
+        match self {
+            Pattern::Glob(_) => "Glob",
+            Pattern::Regex(_) => "Regex",
+            Pattern::Directory(_) => "Verzeichnis",
+            Pattern::Kind(_) => "Art",
+        }
+    }
+
+    // Gleiche Art von Muster mit neuem Typ; der Text bleibt beim Umschalten erhalten
+    pub fn with_type(&self, type_label: &str) -> Pattern {
+        let text = match self {
+            Pattern::Glob(text) | Pattern::Regex(text) | Pattern::Directory(text) => text.clone(),
+            Pattern::Kind(_) => String::new(),
+        };
+        match type_label {
+            "Glob" => Pattern::Glob(text),
+            "Regex" => Pattern::Regex(text),
+            "Verzeichnis" => Pattern::Directory(text),
+            _ => Pattern::Kind(CommandKind::Program),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterRule {
+    pub enabled: bool,
+    pub action: RuleAction,
+    pub pattern: Pattern,
+}
+
+impl FilterRule {
+    fn exclude(pattern: Pattern) -> Self {
+        Self { enabled: true, action: RuleAction::Exclude, pattern }
+    }
+
+    fn include(pattern: Pattern) -> Self {
+        Self { enabled: true, action: RuleAction::Include, pattern }
+    }
+}
+
+// Entspricht den früher fest eingebauten Regeln, ohne Namen mit Punkt oder Plus (python3.11, g++) auszuschließen
+pub fn default_rules() -> Vec<FilterRule> {
+    vec![
+        FilterRule::exclude(Pattern::Glob("?".to_string())),
+        FilterRule::exclude(Pattern::Glob("-*".to_string())),
+    ]
+}
+
+pub fn presets() -> Vec<(&'static str, Vec<FilterRule>)> {
+    vec![
+        (
+            "Shell-Interna ausblenden",
+            vec![
+                FilterRule::exclude(Pattern::Kind(CommandKind::Builtin)),
+                FilterRule::exclude(Pattern::Glob("_*".to_string())),
+                FilterRule::exclude(Pattern::Regex("^[^[:alnum:]]".to_string())),
+                FilterRule::exclude(Pattern::Regex(SHELL_KEYWORDS.to_string())),
+            ],
+        ),
+        ("Nur /usr/local", vec![FilterRule::include(Pattern::Directory("/usr/local".to_string()))]),
+    ]
+}
+
+pub struct FilterResult {
+    // In der Reihenfolge der Eingabe
+    pub visible: Vec<String>,
+    pub stats: FilterStats,
+}
+
+#[derive(Default)]
+pub struct FilterStats {
+    // Pro Regel: wie viele Einträge sie ausblendet (nur Ausschlussregeln)
+    pub hidden_by_rule: Vec<usize>,
+    // Von keiner Einschlussregel erfasst
+    pub not_included: usize,
+    // Pro Regel: Fehlermeldung bei ungültigem Muster; solche Regeln werden ignoriert
+    pub errors: Vec<Option<String>>,
+    pub hidden: usize,
+}
+
+// Befehl -> Verzeichnis des ersten Treffers in PATH, wie angegeben und aufgelöst (/bin -> /usr/bin)
+pub type CommandDirs = HashMap<String, (PathBuf, PathBuf)>;
+
+enum Compiled {
+    Name(Regex),
+    Directory(PathBuf),
+    Kind(CommandKind),
+}
+
+impl Compiled {
+    fn matches(&self, command: &str, kind: CommandKind, dir: Option<&(PathBuf, PathBuf)>) -> bool {
+        match self {
+            Compiled::Name(regex) => regex.is_match(command),
+            Compiled::Directory(prefix) => dir.is_some_and(|(dir, resolved)| dir.starts_with(prefix) || resolved.starts_with(prefix)),
+            Compiled::Kind(expected) => kind == *expected,
+        }
+    }
+}
+
+fn compile(pattern: &Pattern) -> Result<Compiled, String> {
+    match pattern {
+        Pattern::Glob(glob) => Regex::new(&glob_to_regex(glob)).map(Compiled::Name).map_err(|e| e.to_string()),
+        Pattern::Regex(regex) => Regex::new(regex).map(Compiled::Name).map_err(|e| e.to_string()),
+        Pattern::Directory(dir) if dir.trim().is_empty() => Err("Kein Verzeichnis angegeben".to_string()),
+        Pattern::Directory(dir) => Ok(Compiled::Directory(PathBuf::from(dir.trim()))),
+        Pattern::Kind(kind) => Ok(Compiled::Kind(*kind)),
+    }
+}
+
+// "*" beliebig viele Zeichen, "?" genau eines, "[abc]" eine Auswahl, "[!abc]" alles außer; alles andere wörtlich
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut in_class = false;
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '[' if !in_class => {
+                in_class = true;
+                regex.push('[');
+                if chars.next_if_eq(&'!').is_some() {
+                    regex.push('^');
+                }
+            }
+            ']' if in_class => {
+                in_class = false;
+                regex.push(']');
+            }
+            _ if in_class => regex.push(c),
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+// Ein Befehl wird gezeigt, wenn er (sofern es Einschlussregeln gibt) von mindestens einer erfasst
+// und von keiner Ausschlussregel getroffen wird. `dirs` wird erst für eine Verzeichnisregel gefüllt
+// und bleibt bis zum nächsten Einlesen der Befehle gültig.
+pub fn apply(
+    rules: &[FilterRule],
+    commands: &[String],
+    kind_of: impl Fn(&str) -> CommandKind,
+    dirs: &mut Option<CommandDirs>,
+) -> FilterResult {
+    let mut stats = FilterStats {
+        hidden_by_rule: vec![0; rules.len()],
+        errors: vec![None; rules.len()],
+        ..FilterStats::default()
+    };
+    let mut compiled = Vec::new();
+    for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+        match compile(&rule.pattern) {
+            Ok(matcher) => compiled.push((index, rule.action, matcher)),
+            Err(e) => stats.errors[index] = Some(e),
+        }
+    }
+    let has_includes = compiled.iter().any(|(_, action, _)| *action == RuleAction::Include);
+    let empty = CommandDirs::new();
+    let dirs = if compiled.iter().any(|(_, _, matcher)| matches!(matcher, Compiled::Directory(_))) {
+        dirs.get_or_insert_with(command_dirs)
+    } else {
+        &empty
+    };
+
+    let mut visible = Vec::with_capacity(commands.len());
+    for command in commands {
+        let kind = kind_of(command);
+        let dir = dirs.get(command);
+        let included = !has_includes
+            || compiled
+                .iter()
+                .any(|(_, action, matcher)| *action == RuleAction::Include && matcher.matches(command, kind, dir));
+        if !included {
+            stats.not_included += 1;
+            continue;
+        }
+        let mut excluded = false;
+        for (index, _, matcher) in compiled.iter().filter(|(_, action, _)| *action == RuleAction::Exclude) {
+            if matcher.matches(command, kind, dir) {
+                stats.hidden_by_rule[*index] += 1;
+                excluded = true;
+            }
+        }
+        if !excluded {
+            visible.push(command.clone());
+        }
+    }
+    stats.hidden = commands.len() - visible.len();
+    debug!("Command filter hides {} of {} commands", stats.hidden, commands.len());
+    FilterResult { visible, stats }
+}
+
+// Ein Durchlauf pro Verzeichnis statt einer Suche pro Befehl
+fn command_dirs() -> CommandDirs {
+    let mut dirs = CommandDirs::new();
+    let Some(path) = std::env::var_os("PATH") else {
+        return dirs;
+    };
+    for dir in std::env::split_paths(&path) {
+        let Ok(files) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let resolved = fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone());
+        for file in files.flatten() {
+            let name = file.file_name().to_string_lossy().to_string();
+            dirs.entry(name).or_insert_with(|| (dir.clone(), resolved.clone()));
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob_matches(glob: &str, name: &str) -> bool {
+        Regex::new(&glob_to_regex(glob)).unwrap().is_match(name)
+    }
+
+    fn visible(rules: &[FilterRule], commands: &[&str]) -> Vec<String> {
+        let commands: Vec<String> = commands.iter().map(|command| command.to_string()).collect();
+        apply(rules, &commands, |_| CommandKind::Program, &mut Some(CommandDirs::new())).visible
+    }
+
+    #[test]
+    fn glob_wildcards_and_classes() {
+        assert_eq!(glob_to_regex("git-*"), "^git\\-.*$");
+        assert_eq!(glob_to_regex("[!abc]"), "^[^abc]$");
+        assert!(glob_matches("?", "w"));
+        assert!(!glob_matches("?", "ls"));
+        assert!(glob_matches("python3.*", "python3.11"));
+        assert!(!glob_matches("python3.*", "python3x11"));
+        assert!(glob_matches("g++", "g++"));
+        assert!(glob_matches("[gx]z*", "xzcat"));
+        assert!(!glob_matches("[!gx]z*", "xzcat"));
+        assert!(glob_matches("[!gx]z*", "bzip2"));
+    }
+
+    #[test]
+    fn default_rules_keep_dots_and_plus() {
+        let commands = ["w", "-h", "ls", "python3.11", "g++"];
+        assert_eq!(visible(&default_rules(), &commands), ["ls", "python3.11", "g++"]);
+    }
+
+    #[test]
+    fn includes_then_excludes_with_stats() {
+        let rules = vec![
+            FilterRule::include(Pattern::Glob("git*".to_string())),
+            FilterRule::exclude(Pattern::Regex("-(dump|check)$".to_string())),
+            FilterRule { enabled: false, action: RuleAction::Exclude, pattern: Pattern::Glob("gitk".to_string()) },
+            FilterRule::exclude(Pattern::Regex("(".to_string())),
+        ];
+        let result = apply(&rules, &["git", "gitk", "git-dump", "ls", "git-check"].map(String::from), |_| CommandKind::Program, &mut None);
+        assert_eq!(result.visible, ["git", "gitk"]);
+        assert_eq!(result.stats.not_included, 1);
+        assert_eq!(result.stats.hidden_by_rule, [0, 2, 0, 0]);
+        assert_eq!(result.stats.hidden, 3);
+        assert!(result.stats.errors[3].is_some());
+        assert!(result.stats.errors[..3].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn kind_and_directory_rules() {
+        let commands = ["cd", "ll", "rg", "tool"].map(String::from);
+        let kind_of = |name: &str| match name {
+            "cd" => CommandKind::Builtin,
+            "ll" => CommandKind::Alias,
+            _ => CommandKind::Program,
+        };
+        let rules = vec![FilterRule::exclude(Pattern::Kind(CommandKind::Builtin))];
+        assert_eq!(apply(&rules, &commands, kind_of, &mut None).visible, ["ll", "rg", "tool"]);
+
+        let mut dirs = Some(CommandDirs::from([
+            ("rg".to_string(), (PathBuf::from("/usr/bin"), PathBuf::from("/usr/bin"))),
+            ("tool".to_string(), (PathBuf::from("/bin"), PathBuf::from("/usr/local/bin"))),
+        ]));
+        let rules = vec![FilterRule::include(Pattern::Directory("/usr/local".to_string()))];
+        assert_eq!(apply(&rules, &commands, kind_of, &mut dirs).visible, ["tool"]);
+        let rules = vec![FilterRule::include(Pattern::Directory(" ".to_string()))];
+        assert!(apply(&rules, &commands, kind_of, &mut dirs).stats.errors[0].is_some());
+    }
+}
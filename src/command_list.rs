@@ -0,0 +1,144 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+
+pub enum ListClick {
+    Select(String),
+    // Mittelklick oder Strg+Klick
+    OpenInTab(String),
+    Toggle(String),
+}
+
+// Kurz hervorgehobene Änderungen durch neu installierte oder entfernte Programme
+#[derive(Clone, Copy, PartialEq)]
+pub enum RowChange {
+    Added,
+    Removed,
+}
+
+// Solange die neue Suche die vorherige enthält, wird nur die bisherige Trefferliste eingegrenzt.
+// Ohne vorherige Suche (z. B. nach Änderungen an `commands`) wird komplett neu gefiltert.
+// `commands` ist bereits nach Länge sortiert, die Reihenfolge bleibt dabei erhalten.
+pub fn filter(commands: &[String], filtered: &mut Vec<String>, previous_query: Option<&str>, query: &str) {
+    if query.is_empty() {
+        *filtered = commands.to_vec();
+    } else if previous_query.is_some_and(|previous| query.contains(previous)) {
+        filtered.retain(|cmd| cmd.contains(query));
+    } else {
+        *filtered = commands.iter().filter(|cmd| cmd.contains(query)).cloned().collect();
+    }
+}
+
+// Varianten (gcc-12 unter gcc) verschwinden aus der obersten Ebene, solange ihr Elterneintrag mit angezeigt wird.
+// Unter aufgeklappten Befehlen folgen erst die Varianten, dann die Unterbefehle als "git rebase".
+// Enthält die Suche ein Leerzeichen ("git reb"), werden nur die passenden Unterbefehle gezeigt, auch ohne Aufklappen.
+pub fn with_children(
+    matched: &[String],
+    subcommands: &HashMap<String, Vec<String>>,
+    variants: &HashMap<String, Vec<String>>,
+    variant_parents: &HashMap<String, String>,
+    expanded: &HashSet<String>,
+    query: &str,
+) -> Vec<String> {
+    if let Some((parent, sub_query)) = query.split_once(' ') {
+        return subcommands
+            .get(parent)
+            .into_iter()
+            .flatten()
+            .filter(|sub| sub.contains(sub_query.trim()))
+            .map(|sub| format!("{} {}", parent, sub))
+            .collect();
+    }
+    if expanded.is_empty() && variant_parents.is_empty() {
+        return matched.to_vec();
+    }
+    let shown: HashSet<&str> = if variant_parents.is_empty() {
+        HashSet::new()
+    } else {
+        matched.iter().map(String::as_str).collect()
+    };
+    let mut rows = Vec::with_capacity(matched.len());
+    for command in matched {
+        if variant_parents.get(command).is_some_and(|parent| shown.contains(parent.as_str())) {
+            continue;
+        }
+        rows.push(command.clone());
+        if expanded.contains(command) {
+            if let Some(children) = variants.get(command) {
+                rows.extend(children.iter().cloned());
+            }
+            if let Some(children) = subcommands.get(command) {
+                rows.extend(children.iter().map(|sub| format!("{} {}", command, sub)));
+            }
+        }
+    }
+    rows
+}
+
+// Darstellung einer Zeile: Zusatz wie "alias" hinter dem Namen, ob Unterbefehle bzw. Varianten
+// vorhanden und aufgeklappt sind, eine kurze Hervorhebung nach Änderungen in PATH und ob sie eingerückt
+// unter ihrem Elterneintrag steht
+#[derive(Default)]
+pub struct RowStyle {
+    pub note: Option<String>,
+    pub expanded: Option<bool>,
+    pub change: Option<RowChange>,
+    pub nested: bool,
+}
+
+// Zeichnet nur die sichtbaren Zeilen; liefert den angeklickten Befehl (auch für einen neuen Tab) bzw. den auf- oder zugeklappten Eintrag.
+pub fn show(
+    ui: &mut egui::Ui,
+    commands: &[String],
+    selected: Option<&str>,
+    scroll_to_selected: bool,
+    style: impl Fn(&str) -> RowStyle,
+) -> Option<ListClick> {
+    let row_height = ui.spacing().interact_size.y;
+    let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+    if scroll_to_selected {
+        if let Some(index) = selected.and_then(|selected| commands.iter().position(|cmd| cmd == selected)) {
+            let row_pitch = row_height + ui.spacing().item_spacing.y;
+            let offset = index as f32 * row_pitch - (ui.available_height() - row_pitch) / 2.0;
+            scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+        }
+    }
+
+    let mut clicked = None;
+    scroll_area.show_rows(ui, row_height, commands.len(), |ui, row_range| {
+        for command in &commands[row_range] {
+            let is_selected = selected == Some(command.as_str());
+            let style = style(command);
+            let text = match command.split_once(' ') {
+                Some((_, sub)) => format!("    ↳ {}", sub),
+                None if style.nested => format!("    ↳ {}", command),
+                None => command.clone(),
+            };
+            let text = match style.note {
+                Some(note) => format!("{}   [{}]", text, note),
+                None => text,
+            };
+            let added_color = if ui.visuals().dark_mode { egui::Color32::LIGHT_GREEN } else { egui::Color32::DARK_GREEN };
+            let text = match style.change {
+                Some(RowChange::Added) => egui::RichText::new(format!("+ {}", text)).color(added_color),
+                Some(RowChange::Removed) => egui::RichText::new(text).strikethrough().color(ui.visuals().error_fg_color),
+                None => egui::RichText::new(text),
+            };
+            let label = egui::SelectableLabel::new(is_selected, text);
+            ui.horizontal(|ui| {
+                if let Some(expanded) = style.expanded {
+                    let arrow = if expanded { "⏷" } else { "⏵" };
+                    if ui.add(egui::Button::new(arrow).frame(false)).clicked() {
+                        clicked = Some(ListClick::Toggle(command.clone()));
+                    }
+                }
+                let response = ui.add_sized([ui.available_width(), row_height], label);
+                if response.middle_clicked() || (response.clicked() && ui.input(|i| i.modifiers.command)) {
+                    clicked = Some(ListClick::OpenInTab(command.clone()));
+                } else if response.clicked() {
+                    clicked = Some(ListClick::Select(command.clone()));
+                }
+            });
+        }
+    });
+    clicked
+}
@@ -0,0 +1,590 @@
+use crate::explain::split_words;
+use crate::shell::{bash_completion_dirs, fish_completion_dirs};
+use log::{debug, error};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpecSource {
+    Fish,
+    Bash,
+}
+
+impl SpecSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            SpecSource::Fish => "fish",
+            SpecSource::Bash => "bash-completion",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SpecFile {
+    pub path: PathBuf,
+    pub source: SpecSource,
+}
+
+pub struct CompletionOption {
+    pub flags: Vec<String>,
+    pub description: String,
+    pub takes_argument: bool,
+}
+
+// Ein Knoten pro Befehl bzw. Unterbefehl; `values` sind feste Argumente ohne eigene Optionen
+#[derive(Default)]
+pub struct CompletionNode {
+    pub name: String,
+    pub description: String,
+    pub options: Vec<CompletionOption>,
+    pub values: Vec<String>,
+    pub children: Vec<CompletionNode>,
+}
+
+pub struct CompletionSpec {
+    pub file: SpecFile,
+    pub root: CompletionNode,
+}
+
+impl CompletionNode {
+    fn child_mut(&mut self, name: &str) -> &mut CompletionNode {
+        match self.children.iter().position(|child| child.name == name) {
+            Some(index) => &mut self.children[index],
+            None => {
+                self.children.push(CompletionNode { name: name.to_string(), ..Default::default() });
+                self.children.last_mut().unwrap()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty() && self.values.is_empty() && self.children.is_empty()
+    }
+}
+
+// Ordnet den Befehlen aus `cli.rs` ihre Vervollständigungsdatei zu.
+// fish-Dateien sind strukturiert und werden bevorzugt, bash-completion nur heuristisch ausgewertet.
+pub fn spec_files(commands: &[String]) -> HashMap<String, SpecFile> {
+    let mut available: HashMap<String, SpecFile> = HashMap::new();
+    collect_files(&mut available, &bash_completion_dirs(), SpecSource::Bash);
+    collect_files(&mut available, &fish_completion_dirs(), SpecSource::Fish);
+    available.retain(|name, _| commands.contains(name));
+    debug!("Found completion specs for {} commands", available.len());
+    available
+}
+
+// Frühere Verzeichnisse haben Vorrang; fish überschreibt bash, weil es zuletzt gesammelt wird
+fn collect_files(available: &mut HashMap<String, SpecFile>, dirs: &[PathBuf], source: SpecSource) {
+    let mut found: HashMap<String, SpecFile> = HashMap::new();
+    for dir in dirs {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = match source {
+                SpecSource::Fish => file_name.strip_suffix(".fish"),
+                SpecSource::Bash => Some(file_name.strip_suffix(".bash").unwrap_or(&file_name)),
+            };
+            let Some(name) = name.filter(|name| !name.starts_with('_') && !name.starts_with('.')) else {
+                continue;
+            };
+            found.entry(name.to_string()).or_insert(SpecFile { path: entry.path(), source });
+        }
+    }
+    available.extend(found);
+}
+
+pub fn load(command: &str, file: &SpecFile) -> Option<CompletionSpec> {
+    let content = match fs::read_to_string(&file.path) {
+        Ok(content) => content,
+        Err(e) => {
+            error!("Failed to read completion file {}: {}", file.path.display(), e);
+            return None;
+        }
+    };
+    let mut root = CompletionNode { name: command.to_string(), ..Default::default() };
+    match file.source {
+        SpecSource::Fish => parse_fish(&content, command, &mut root),
+        SpecSource::Bash => parse_bash(&content, &mut root),
+    }
+    root.children.sort_by(|a, b| a.name.cmp(&b.name));
+    (!root.is_empty()).then(|| CompletionSpec { file: file.clone(), root })
+}
+
+// Wohin eine `complete`-Zeile gehört, abgeleitet aus ihrer -n-Bedingung
+enum Target {
+    Root,
+    Subcommand,
+    Within(Vec<String>),
+    Unknown,
+}
+
+fn classify_condition(condition: Option<&str>) -> Target {
+    let Some(condition) = condition else {
+        return Target::Root;
+    };
+    // Nur der erste Teil von "a; and b" bzw. "a && b" bestimmt die Ebene
+    let first = condition
+        .split([';', '&'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let words: Vec<&str> = first.split_whitespace().collect();
+    let Some(function) = words.first() else {
+        return Target::Root;
+    };
+    if *function == "not" {
+        return match words.get(1) {
+            Some(function) if function.contains("seen_subcommand_from") => Target::Subcommand,
+            _ => Target::Unknown,
+        };
+    }
+    if function.contains("use_subcommand")
+        || function.contains("needs_command")
+        || function.contains("needs_subcommand")
+        || function.contains("is_first_arg")
+        || function.contains("is_first_token")
+        || function.contains("no_subcommand")
+    {
+        return Target::Subcommand;
+    }
+    if function.contains("seen_subcommand_from") || function.contains("using_command") {
+        let names: Vec<String> = words[1..]
+            .iter()
+            .filter(|word| !word.starts_with('$') && !word.starts_with('-'))
+            .map(|word| word.to_string())
+            .collect();
+        if !names.is_empty() {
+            return Target::Within(names);
+        }
+    }
+    Target::Unknown
+}
+
+#[derive(Default)]
+struct CompleteLine {
+    commands: Vec<String>,
+    condition: Option<String>,
+    arguments: Option<String>,
+    description: String,
+    flags: Vec<String>,
+    takes_argument: bool,
+}
+
+// complete -c git -n '__fish_seen_subcommand_from rebase' -s i -l interactive -d 'Interactive rebase'
+fn parse_complete_line(words: &[String]) -> CompleteLine {
+    let mut line = CompleteLine::default();
+    let mut i = 1;
+    while i < words.len() {
+        let word = &words[i];
+        i += 1;
+        // Langform: --long-option=foo oder --long-option foo
+        if let Some(long) = word.strip_prefix("--") {
+            let (name, inline) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (long, None),
+            };
+            let short = match name {
+                "command" => 'c',
+                "condition" => 'n',
+                "arguments" => 'a',
+                "description" => 'd',
+                "short-option" => 's',
+                "long-option" => 'l',
+                "old-option" => 'o',
+                "wraps" => 'w',
+                "path" => 'p',
+                "require-parameter" => 'r',
+                "exclusive" => 'x',
+                _ => continue,
+            };
+            let value = if takes_value(short) {
+                inline.or_else(|| {
+                    i += 1;
+                    words.get(i - 1).cloned()
+                })
+            } else {
+                None
+            };
+            apply_flag(&mut line, short, value);
+            continue;
+        }
+        // Kurzform wie bei getopt, auch zusammengefasst: -xa 'add remove'
+        let Some(shorts) = word.strip_prefix('-') else {
+            continue;
+        };
+        for (offset, short) in shorts.char_indices() {
+            if takes_value(short) {
+                let rest = &shorts[offset + short.len_utf8()..];
+                let value = if rest.is_empty() {
+                    i += 1;
+                    words.get(i - 1).cloned()
+                } else {
+                    Some(rest.to_string())
+                };
+                apply_flag(&mut line, short, value);
+                break;
+            }
+            apply_flag(&mut line, short, None);
+        }
+    }
+    line
+}
+
+fn takes_value(short: char) -> bool {
+    matches!(short, 'c' | 'n' | 'a' | 'd' | 's' | 'l' | 'o' | 'w' | 'p')
+}
+
+fn apply_flag(line: &mut CompleteLine, short: char, value: Option<String>) {
+    match (short, value) {
+        ('c', Some(value)) | ('p', Some(value)) => line.commands.push(value),
+        ('n', Some(value)) => line.condition = Some(value),
+        ('a', Some(value)) => line.arguments = Some(value),
+        ('d', Some(value)) => line.description = value,
+        ('s', Some(value)) => line.flags.push(format!("-{}", value)),
+        ('l', Some(value)) => line.flags.push(format!("--{}", value)),
+        ('o', Some(value)) => line.flags.push(format!("-{}", value)),
+        ('r', _) | ('x', _) => line.takes_argument = true,
+        _ => {}
+    }
+}
+
+fn parse_fish(content: &str, command: &str, root: &mut CompletionNode) {
+    // Fortsetzungszeilen mit "\" am Ende zusammenfügen
+    let joined = content.replace("\\\n", " ");
+    for text in joined.lines().map(str::trim) {
+        if !text.starts_with("complete ") {
+            continue;
+        }
+        let words = split_words(text);
+        let line = parse_complete_line(&words);
+        if !line.commands.is_empty() && !line.commands.iter().any(|name| name == command) {
+            continue;
+        }
+        let target = classify_condition(line.condition.as_deref());
+        if !line.flags.is_empty() {
+            let option = CompletionOption {
+                flags: line.flags,
+                description: line.description,
+                takes_argument: line.takes_argument,
+            };
+            match target {
+                Target::Within(names) => {
+                    for name in &names[1..] {
+                        root.child_mut(name).options.push(CompletionOption {
+                            flags: option.flags.clone(),
+                            description: option.description.clone(),
+                            takes_argument: option.takes_argument,
+                        });
+                    }
+                    root.child_mut(&names[0]).options.push(option);
+                }
+                _ => root.options.push(option),
+            }
+            continue;
+        }
+        // Dynamisch erzeugte Argumente wie "(__fish_git_branches)" lassen sich nicht statisch auflisten
+        let Some(arguments) = line.arguments.filter(|arguments| !arguments.contains('(') && !arguments.contains('$')) else {
+            continue;
+        };
+        for argument in arguments.split_whitespace() {
+            let (name, description) = match argument.split_once('\t') {
+                Some((name, description)) => (name, description.to_string()),
+                None => (argument, line.description.clone()),
+            };
+            match &target {
+                Target::Subcommand => {
+                    let child = root.child_mut(name);
+                    if child.description.is_empty() {
+                        child.description = description;
+                    }
+                }
+                Target::Root => {
+                    if !root.values.iter().any(|value| value == name) {
+                        root.values.push(name.to_string());
+                    }
+                }
+                Target::Within(names) => {
+                    for parent in names {
+                        let child = root.child_mut(parent);
+                        if !child.values.iter().any(|value| value == name) {
+                            child.values.push(name.to_string());
+                        }
+                    }
+                }
+                Target::Unknown => {}
+            }
+        }
+    }
+}
+
+// bash-completion-Dateien sind Skripte. Ausgewertet werden die Wortlisten von "compgen -W";
+// steht eine Liste in einem case-Zweig, der einem Unterbefehl entspricht, gehört sie zu diesem.
+fn parse_bash(content: &str, root: &mut CompletionNode) {
+    let variables = bash_variables(content);
+    let mut lists: Vec<(Vec<String>, Vec<String>)> = Vec::new();
+    let mut case_labels: Vec<String> = Vec::new();
+    let joined = content.replace("\\\n", " ");
+    let mut rest = joined.as_str();
+    while !rest.is_empty() {
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        let line = rest[..line_end].trim();
+        if let Some(labels) = case_label(line) {
+            case_labels = labels;
+        } else if line.starts_with(";;") || line == "esac" {
+            case_labels.clear();
+        }
+        if let Some(position) = rest[..line_end].find("-W") {
+            // Die Wortliste kann über mehrere Zeilen gehen, daher ab "-W" im Rest weiterlesen
+            let (words, consumed) = word_list(&rest[position + 2..], &variables);
+            lists.push((case_labels.clone(), words));
+            let next = position + 2 + consumed;
+            rest = &rest[next.max(line_end).min(rest.len())..];
+        } else {
+            rest = &rest[(line_end + 1).min(rest.len())..];
+        }
+    }
+
+    for (labels, words) in &lists {
+        if labels.is_empty() {
+            for word in words {
+                add_bash_word(root, word);
+            }
+        }
+    }
+    let subcommands: Vec<String> = root.children.iter().map(|child| child.name.clone()).collect();
+    for (labels, words) in lists {
+        for label in labels.iter().filter(|label| subcommands.contains(label)) {
+            let child = root.child_mut(label);
+            for word in &words {
+                add_bash_word(child, word);
+            }
+        }
+    }
+}
+
+fn add_bash_word(node: &mut CompletionNode, word: &str) {
+    if word.starts_with('-') {
+        let flag = word.trim_end_matches('=');
+        if !node.options.iter().any(|option| option.flags.iter().any(|existing| existing == flag)) {
+            node.options.push(CompletionOption {
+                flags: vec![flag.to_string()],
+                description: String::new(),
+                takes_argument: word.ends_with('='),
+            });
+        }
+    } else if !node.children.iter().any(|child| child.name == word) {
+        node.children.push(CompletionNode { name: word.to_string(), ..Default::default() });
+    }
+}
+
+// "install|reinstall)" -> ["install", "reinstall"]; Muster mit Platzhaltern wie "-*)" zählen nicht
+fn case_label(line: &str) -> Option<Vec<String>> {
+    let label = line.strip_suffix(')')?.trim().trim_start_matches('(');
+    let labels: Vec<String> = label.split('|').map(|part| part.trim().trim_matches(['"', '\'']).to_string()).collect();
+    let valid = labels
+        .iter()
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_'));
+    valid.then_some(labels)
+}
+
+// local opts='--foo --bar' bzw. local COMMANDS=( "list" "show" )
+fn bash_variables(content: &str) -> HashMap<String, Vec<String>> {
+    let mut variables = HashMap::new();
+    let mut rest = content;
+    while let Some(position) = rest.find('=') {
+        let before = &rest[..position];
+        let name: String = before
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect::<String>()
+            .chars()
+            .rev()
+            .collect();
+        let after = &rest[position + 1..];
+        let is_assignment = !name.is_empty()
+            && before[..before.len() - name.len()].ends_with(char::is_whitespace)
+            && after.starts_with(['\'', '"', '(']);
+        if is_assignment {
+            let close = match after.chars().next() {
+                Some('(') => ')',
+                Some(quote) => quote,
+                None => break,
+            };
+            if let Some(end) = after[1..].find(close) {
+                let words = after[1..end + 1]
+                    .split_whitespace()
+                    .map(|word| word.trim_matches(['"', '\'']).to_string())
+                    .filter(|word| is_plain_word(word))
+                    .collect();
+                variables.insert(name, words);
+                rest = &after[end + 2..];
+                continue;
+            }
+        }
+        rest = after;
+    }
+    variables
+}
+
+// Liest die Liste hinter "-W" bis zum Ende des (ggf. zusammengesetzten) Arguments.
+// Liefert die Wörter und die Anzahl verbrauchter Bytes.
+fn word_list(text: &str, variables: &HashMap<String, Vec<String>>) -> (Vec<String>, usize) {
+    let start = text.len() - text.trim_start_matches([' ', '\t']).len();
+    let mut raw = String::new();
+    let mut chars = text[start..].char_indices().peekable();
+    let mut consumed = text.len();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            // '--foo'"$OPTS": aneinandergehängte Teile getrennt halten, damit Variablen erkennbar bleiben
+            '\'' | '"' => {
+                raw.push(' ');
+                for (_, inner) in chars.by_ref() {
+                    if inner == c {
+                        break;
+                    }
+                    raw.push(inner);
+                }
+                raw.push(' ');
+            }
+            c if c.is_whitespace() => {
+                consumed = start + index;
+                break;
+            }
+            _ => raw.push(c),
+        }
+    }
+
+    let mut words = Vec::new();
+    for word in without_command_substitutions(&raw).split_whitespace() {
+        if let Some(name) = variable_name(word) {
+            if let Some(values) = variables.get(name) {
+                words.extend(values.iter().cloned());
+            }
+        } else if is_plain_word(word) {
+            words.push(word.to_string());
+        }
+    }
+    (words, consumed)
+}
+
+// "$( command ls $dir )" erzeugt Wörter erst zur Laufzeit
+fn without_command_substitutions(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("$(") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start..].find(')') {
+            Some(end) => &rest[start + end + 1..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+// "$opts", "${COMMANDS[@]}", "${COMMANDS[*]}" -> Variablenname
+fn variable_name(word: &str) -> Option<&str> {
+    let name = word.strip_prefix('$')?;
+    let name = name.strip_prefix('{').and_then(|name| name.strip_suffix('}')).unwrap_or(name);
+    let name = name.strip_suffix("[@]").or_else(|| name.strip_suffix("[*]")).unwrap_or(name);
+    name.chars().all(|c| c.is_alphanumeric() || c == '_').then_some(name)
+}
+
+fn is_plain_word(word: &str) -> bool {
+    !word.is_empty()
+        && word.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '=' | '.' | ':' | '+'))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fish(content: &str) -> CompletionNode {
+        let mut root = CompletionNode { name: "git".to_string(), ..Default::default() };
+        parse_fish(content, "git", &mut root);
+        root
+    }
+
+    fn bash(content: &str) -> CompletionNode {
+        let mut root = CompletionNode::default();
+        parse_bash(content, &mut root);
+        root
+    }
+
+    fn flags(node: &CompletionNode) -> Vec<&str> {
+        node.options.iter().flat_map(|option| option.flags.iter().map(String::as_str)).collect()
+    }
+
+    fn child<'a>(node: &'a CompletionNode, name: &str) -> &'a CompletionNode {
+        node.children.iter().find(|child| child.name == name).expect("child exists")
+    }
+
+    #[test]
+    fn complete_line_with_short_long_and_combined_flags() {
+        let line = parse_complete_line(&split_words("complete -c git -n '__fish_seen_subcommand_from rebase' -s i -l interactive -d 'Interactive rebase'"));
+        assert_eq!(line.commands, ["git"]);
+        assert_eq!(line.condition.as_deref(), Some("__fish_seen_subcommand_from rebase"));
+        assert_eq!(line.flags, ["-i", "--interactive"]);
+        assert_eq!(line.description, "Interactive rebase");
+
+        let line = parse_complete_line(&split_words("complete --command=git --long-option=color -rfa 'always never'"));
+        assert_eq!(line.commands, ["git"]);
+        assert_eq!(line.flags, ["--color"]);
+        assert!(line.takes_argument);
+        assert_eq!(line.arguments.as_deref(), Some("always never"));
+    }
+
+    #[test]
+    fn fish_subcommands_and_their_options() {
+        let root = fish(concat!(
+            "complete -c git -n __fish_git_needs_command -a rebase -d 'Reapply commits'\n",
+            "complete -c git -n '__fish_use_subcommand' -a 'commit status' -d 'Record changes'\n",
+            "complete -c git -n '__fish_seen_subcommand_from rebase' -s i -l interactive -d 'Interactive rebase'\n",
+            "complete -c git -l version -d 'Print version'\n",
+            "complete -c gitk -l all\n",
+            "complete -c git -n '__fish_seen_subcommand_from checkout' -a '(__fish_git_branches)'\n",
+        ));
+        assert_eq!(flags(&root), ["--version"]);
+        assert_eq!(child(&root, "rebase").description, "Reapply commits");
+        assert_eq!(flags(child(&root, "rebase")), ["-i", "--interactive"]);
+        assert_eq!(child(&root, "commit").description, "Record changes");
+        assert_eq!(child(&root, "status").description, "Record changes");
+        assert!(root.children.iter().all(|child| child.name != "checkout" || child.values.is_empty()));
+    }
+
+    #[test]
+    fn bash_word_lists_from_variables_and_case_branches() {
+        let root = bash(concat!(
+            "_tool() {\n",
+            "    local commands='install remove'\n",
+            "    local opts=( \"--help\" \"--color=\" )\n",
+            "    case $prev in\n",
+            "        install|remove)\n",
+            "            COMPREPLY=( $(compgen -W '--force $(ls /tmp)' -- \"$cur\") )\n",
+            "            ;;\n",
+            "    esac\n",
+            "    COMPREPLY=( $(compgen -W \"$commands ${opts[@]}\" -- \"$cur\") )\n",
+            "}\n",
+        ));
+        let names: Vec<&str> = root.children.iter().map(|child| child.name.as_str()).collect();
+        assert_eq!(names, ["install", "remove"]);
+        assert_eq!(flags(&root), ["--help", "--color"]);
+        assert!(root.options[1].takes_argument);
+        assert_eq!(flags(child(&root, "install")), ["--force"]);
+        assert_eq!(flags(child(&root, "remove")), ["--force"]);
+    }
+
+    #[test]
+    fn case_labels_and_variable_names() {
+        assert_eq!(case_label("install|reinstall)"), Some(vec!["install".to_string(), "reinstall".to_string()]));
+        assert_eq!(case_label("-*)"), None);
+        assert_eq!(variable_name("${COMMANDS[@]}"), Some("COMMANDS"));
+        assert_eq!(variable_name("$opts"), Some("opts"));
+        assert_eq!(variable_name("--all"), None);
+    }
+}
@@ -0,0 +1,36 @@
+use log::error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+// Alle Dateien der Anwendung liegen direkt im Konfigurationsverzeichnis des Benutzers
+pub fn config_path(file_name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(file_name))
+}
+
+pub fn load_json<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let path = config_path(file_name)?;
+    let contents = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn save_json<T: Serialize + ?Sized>(file_name: &str, value: &T) {
+    let Some(path) = config_path(file_name) else {
+        return;
+    };
+    match serde_json::to_string(value) {
+        Ok(json) => {
+            if let Err(e) = fs::write(&path, json) {
+                error!("Failed to write {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => error!("Failed to serialize {}: {}", path.display(), e),
+    }
+}
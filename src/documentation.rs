@@ -0,0 +1,79 @@
+use crate::manpage::get_manpage;
+use crate::shell::ShellEntry;
+use crate::subcommands;
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// Angezeigt, bis die Dokumentation im Hintergrund geholt ist
+pub const LOADING_TEXT: &str = "Wird geladen …";
+
+// Dokumentation, für die ein Programm gestartet werden muss
+#[derive(Clone)]
+pub enum DocumentationJob {
+    // `help`, zshbuiltins oder __fish_print_help, ersatzweise die Manpage
+    Builtin(ShellEntry),
+    // Alias oder Funktion: Definition (falls die Shell sie beim Auflisten nicht mitgeliefert hat, etwa fish)
+    // und bei Aliasen die Manpage des Zielprogramms. Die geholte Definition steht danach im Eintrag.
+    Definition(ShellEntry),
+    // Manpage oder `<tool> help <sub>` eines Unterbefehls wie "git rebase"
+    Subcommand { parent: String, sub: String, description: Option<String> },
+}
+
+impl DocumentationJob {
+    fn run(&mut self) -> io::Result<String> {
+        match self {
+            DocumentationJob::Builtin(entry) => {
+                entry.shell.backend().builtin_help(&entry.name).or_else(|_| get_manpage(&entry.name))
+            }
+            DocumentationJob::Definition(entry) => {
+                let definition = match &entry.definition {
+                    Some(definition) => definition.clone(),
+                    None => entry.shell.backend().function_definition(&entry.name)?,
+                };
+                entry.definition = Some(definition.clone());
+                let mut documentation = entry.documentation(&definition);
+                if let Some(target) = entry.alias_target().filter(|target| *target != entry.name) {
+                    if let Ok(manpage) = get_manpage(&target) {
+                        documentation.push('\n');
+                        documentation.push_str(&manpage);
+                    }
+                }
+                Ok(documentation)
+            }
+            DocumentationJob::Subcommand { parent, sub, description } => {
+                subcommands::documentation(parent, sub, description.as_deref())
+            }
+        }
+    }
+}
+
+pub struct Fetched {
+    pub command: String,
+    pub job: DocumentationJob,
+    pub text: io::Result<String>,
+}
+
+// Jede Anfrage bekommt einen eigenen Thread, damit eine hängende Shell die nächste Auswahl nicht aufhält
+pub struct DocumentationFetch {
+    sender: Sender<Fetched>,
+    pub results: Receiver<Fetched>,
+}
+
+impl Default for DocumentationFetch {
+    fn default() -> Self {
+        let (sender, results) = mpsc::channel();
+        Self { sender, results }
+    }
+}
+
+impl DocumentationFetch {
+    pub fn request(&self, command: &str, mut job: DocumentationJob) {
+        let sender = self.sender.clone();
+        let command = command.to_string();
+        thread::spawn(move || {
+            let text = job.run();
+            let _ = sender.send(Fetched { command, job, text });
+        });
+    }
+}
@@ -0,0 +1,260 @@
+use crate::app::MyApp;
+use crate::builder::shell_quote;
+use crate::manpage::{body_indent, is_examples_section, is_section_heading};
+use eframe::egui;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::Command;
+use std::thread;
+
+pub const EXAMPLE_TARGET_KEY: &str = "cli_organizer_example_target";
+
+// Wohin "Einfügen" einen Beispielbefehl zusätzlich zur Zwischenablage schickt
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExampleTarget {
+    #[default]
+    Clipboard,
+    TmuxBuffer,
+    NamedPipe(String),
+}
+
+pub struct PlaceholderForm {
+    pub command: String,
+    pub values: Vec<(String, String)>,
+}
+
+impl PlaceholderForm {
+    pub fn new(command: &str) -> Self {
+        let values = placeholders(command).into_iter().map(|name| (name, String::new())).collect();
+        Self { command: command.to_string(), values }
+    }
+
+    pub fn filled(&self) -> String {
+        let values: HashMap<&str, &str> = self.values
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        fill_placeholders(&self.command, &values)
+    }
+}
+
+// Beispielzeilen: alles mit Prompt ("$ ", "% ") und tiefer eingerückte Befehle im Abschnitt EXAMPLES.
+// Zeilen, die mit "\" enden, werden mit der Folgezeile zusammengefügt.
+pub fn extract_examples(manpage: &str) -> Vec<String> {
+    let body_indent = body_indent(manpage);
+    let mut section = "";
+    let mut examples: Vec<String> = Vec::new();
+    let mut continuation: Option<String> = None;
+
+    for line in manpage.lines() {
+        if is_section_heading(line) {
+            section = line.trim();
+            continuation = None;
+            continue;
+        }
+        let trimmed = line.trim();
+        if let Some(mut pending) = continuation.take() {
+            pending.push(' ');
+            pending.push_str(trimmed.trim_end_matches('\\').trim_end());
+            if trimmed.ends_with('\\') {
+                continuation = Some(pending);
+            } else {
+                examples.push(pending);
+            }
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let candidate = match trimmed.strip_prefix("$ ").or_else(|| trimmed.strip_prefix("% ")) {
+            Some(rest) => Some(rest.trim()),
+            None if is_examples_section(section) && indent > body_indent && looks_like_command(trimmed) => Some(trimmed),
+            None => None,
+        };
+        if let Some(command) = candidate {
+            if command.ends_with('\\') {
+                continuation = Some(command.trim_end_matches('\\').trim_end().to_string());
+            } else {
+                examples.push(command.to_string());
+            }
+        }
+    }
+
+    let mut seen = Vec::new();
+    examples.retain(|example| {
+        if seen.contains(example) {
+            false
+        } else {
+            seen.push(example.clone());
+            true
+        }
+    });
+    examples
+}
+
+fn looks_like_command(line: &str) -> bool {
+    let starts_like_command = line
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || matches!(c, '.' | '/' | '~' | '_'));
+    // Erklärender Text endet meist mit einem Satzzeichen
+    starts_like_command && !line.ends_with('.') && !line.ends_with(':')
+}
+
+// Platzhalter am Anfang von `text`: Name und Länge von "{{name}}" bzw. "<name>"
+fn placeholder_at(text: &str) -> Option<(&str, usize)> {
+    if let Some(inner) = text.strip_prefix("{{") {
+        inner.find("}}").map(|end| (&inner[..end], 2 + end + 2))
+    } else if let Some(inner) = text.strip_prefix('<') {
+        inner
+            .find('>')
+            .filter(|end| *end > 0 && !inner[..*end].contains([' ', '<']))
+            .map(|end| (&inner[..end], 1 + end + 1))
+    } else {
+        None
+    }
+}
+
+// Findet {{name}} (tldr-Stil) und <name> in der Reihenfolge ihres ersten Auftretens
+pub fn placeholders(command: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = command;
+    while let Some(start) = rest.find(['{', '<']) {
+        let tail = &rest[start..];
+        match placeholder_at(tail) {
+            Some((name, length)) => {
+                if !names.iter().any(|known| known == name) {
+                    names.push(name.to_string());
+                }
+                rest = &tail[length..];
+            }
+            None => rest = &tail[1..],
+        }
+    }
+    names
+}
+
+// Werte werden für die Shell gequotet, damit "my file; rm -rf ~" ein einzelnes Argument bleibt.
+// Steht der Platzhalter schon in Anführungszeichen ("{{pattern}}"), ersetzt das Quoting diese.
+// Ein Durchlauf, damit Platzhalter in eingesetzten Werten nicht erneut ersetzt werden.
+pub fn fill_placeholders(command: &str, values: &HashMap<&str, &str>) -> String {
+    let mut filled = String::new();
+    let mut rest = command;
+    while let Some(start) = rest.find(['{', '<']) {
+        filled.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let Some((value, length)) = placeholder_at(tail).and_then(|(name, length)| Some((values.get(name)?, length))) else {
+            filled.push_str(&tail[..1]);
+            rest = &tail[1..];
+            continue;
+        };
+        rest = &tail[length..];
+        if let Some(quote) = filled.chars().last().filter(|c| matches!(c, '"' | '\'')) {
+            if let Some(after) = rest.strip_prefix(quote) {
+                filled.pop();
+                rest = after;
+            }
+        }
+        filled.push_str(&shell_quote(value));
+    }
+    filled.push_str(rest);
+    filled
+}
+
+pub fn send_to_tmux(command: &str) {
+    match Command::new("tmux").args(["set-buffer", "--", command]).output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => error!("tmux set-buffer failed: {}", String::from_utf8_lossy(&output.stderr)),
+        Err(e) => error!("Failed to run tmux: {}", e),
+    }
+}
+
+// Das Öffnen einer FIFO blockiert, bis ein Leser da ist; deshalb in einem eigenen Thread
+pub fn send_to_pipe(path: &str, command: &str) {
+    let path = path.to_string();
+    let line = format!("{}\n", command);
+    thread::spawn(move || {
+        let result = OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .and_then(|mut pipe| pipe.write_all(line.as_bytes()));
+        if let Err(e) = result {
+            error!("Failed to write to {}: {}", path, e);
+        }
+    });
+}
+
+impl MyApp {
+    pub fn copy_example(&self, ctx: &egui::Context, command: &str) {
+        ctx.output_mut(|o| o.copied_text = command.to_string());
+        match &self.example_target {
+            ExampleTarget::Clipboard => {}
+            ExampleTarget::TmuxBuffer => send_to_tmux(command),
+            ExampleTarget::NamedPipe(path) if !path.is_empty() => send_to_pipe(path, command),
+            ExampleTarget::NamedPipe(_) => {}
+        }
+    }
+
+    // Tastenkürzel: Beispiele aufklappen und das erste übernehmen, mit Platzhaltern über das Formular
+    pub fn open_first_example(&mut self, ctx: &egui::Context) {
+        let Some(example) = self.examples.first().cloned() else {
+            return;
+        };
+        self.expand_examples = true;
+        if placeholders(&example).is_empty() {
+            self.copy_example(ctx, &example);
+        } else {
+            self.placeholder_form = Some(PlaceholderForm::new(&example));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_in_order_of_first_use() {
+        assert_eq!(placeholders("tar -czf {{target.tar.gz}} {{file1}} {{file1}}"), ["target.tar.gz", "file1"]);
+        assert_eq!(placeholders("cp <source> <dest> 2>/dev/null"), ["source", "dest"]);
+        assert!(placeholders("echo $((1 < 2)) | sort -k {1}").is_empty());
+    }
+
+    #[test]
+    fn filled_values_are_quoted_for_the_shell() {
+        let values = HashMap::from([("file", "my file; rm -rf ~"), ("dest", "backup"), ("pattern", "it's")]);
+        assert_eq!(fill_placeholders("cp {{file}} <dest>", &values), "cp 'my file; rm -rf ~' backup");
+        assert_eq!(fill_placeholders("grep \"{{pattern}}\" <dest>", &values), "grep 'it'\\''s' backup");
+        // Fehlende Werte bleiben als Platzhalter stehen, eingesetzte werden nicht erneut ersetzt
+        assert_eq!(fill_placeholders("mv {{file}} {{other}}", &HashMap::from([("file", "{{other}}")])), "mv '{{other}}' {{other}}");
+    }
+
+    #[test]
+    fn examples_from_prompts_and_the_examples_section() {
+        let manpage = concat!(
+            "TAR(1)     General Commands Manual     TAR(1)\n",
+            "\n",
+            "DESCRIPTION\n",
+            "       Create an archive:\n",
+            "       $ tar -cf archive.tar foo bar\n",
+            "\n",
+            "EXAMPLES\n",
+            "       Extract all files:\n",
+            "\n",
+            "           tar -xf archive.tar\n",
+            "\n",
+            "           tar -czf out.tgz \\\n",
+            "               --exclude=*.o src\n",
+            "\n",
+            "           This is explained here.\n",
+            "       $ tar -cf archive.tar foo bar\n",
+        );
+        assert_eq!(
+            extract_examples(manpage),
+            ["tar -cf archive.tar foo bar", "tar -xf archive.tar", "tar -czf out.tgz --exclude=*.o src"]
+        );
+    }
+}
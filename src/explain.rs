@@ -0,0 +1,530 @@
+use crate::app::MyApp;
+use crate::manpage::get_manpage;
+use crate::options::{parse_options, ManOption};
+use log::debug;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+#[derive(Clone, PartialEq)]
+enum TokenKind {
+    Word,
+    Operator,
+}
+
+struct Token {
+    text: String,
+    kind: TokenKind,
+}
+
+pub struct Explanation {
+    pub token: String,
+    pub text: String,
+    pub known: bool,
+}
+
+pub struct ExplainedCommand {
+    pub program: String,
+    pub has_manpage: bool,
+    pub explanations: Vec<Explanation>,
+}
+
+// Längere Operatoren zuerst, damit ">&" nicht als ">" und "&" gelesen wird
+const OPERATORS: [&str; 20] = [
+    "||", "&&", "&>>", "&>", ">>", ">&", "<&", "2>&1", "2>&", "2>>", "2>", "$(", "|", ";", "&", ">", "<", "(", ")", "`",
+];
+// Stehen an der Stelle eines Programms, sind aber keins; das Programm folgt danach
+const SHELL_KEYWORDS: [&str; 15] = [
+    "if", "then", "else", "elif", "fi", "do", "done", "while", "until", "for", "case", "esac", "select", "function", "!",
+];
+// Starten das Programm, das ihnen als Argument folgt
+const WRAPPERS: [&str; 17] = [
+    "builtin", "busybox", "command", "doas", "env", "eval", "exec", "ionice", "nice", "nohup", "setsid", "stdbuf",
+    "strace", "sudo", "time", "timeout", "xargs",
+];
+// Optionen der Wrapper, deren Wert als eigenes Wort folgt (sudo -u root rm ...)
+const WRAPPER_VALUE_OPTIONS: [(&str, &[&str]); 9] = [
+    ("sudo", &["-u", "-g", "-C", "-D", "-h", "-p", "-r", "-t", "-U", "--user", "--group", "--chdir", "--prompt"]),
+    ("doas", &["-u", "-C"]),
+    ("env", &["-u", "-C", "--unset", "--chdir"]),
+    ("nice", &["-n", "--adjustment"]),
+    ("ionice", &["-c", "-n", "-p", "--class", "--classdata"]),
+    ("timeout", &["-s", "-k", "--signal", "--kill-after"]),
+    ("stdbuf", &["-i", "-o", "-e", "--input", "--output", "--error"]),
+    ("strace", &["-e", "-o", "-p", "-s", "-u", "-E"]),
+    ("xargs", &["-I", "-d", "-E", "-L", "-n", "-P", "-s", "-a", "--delimiter", "--max-args", "--max-procs", "--arg-file"]),
+];
+// Führen das Argument nach -c als Skript aus
+const SHELLS: [&str; 7] = ["sh", "bash", "dash", "zsh", "ksh", "mksh", "fish"];
+const FIND_EXEC: [&str; 4] = ["-exec", "-execdir", "-ok", "-okdir"];
+
+fn flush_word(current: &mut String, in_word: &mut bool, tokens: &mut Vec<Token>) {
+    if *in_word {
+        tokens.push(Token { text: std::mem::take(current), kind: TokenKind::Word });
+        *in_word = false;
+    }
+}
+
+// Einfache Shell-Zerlegung: Anführungszeichen, Backslash-Escapes und Operatoren wie |, &&, ; und Umleitungen
+fn tokenize(line: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            flush_word(&mut current, &mut in_word, &mut tokens);
+            i += 1;
+            continue;
+        }
+        let rest: String = chars[i..].iter().take(4).collect();
+        if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            // "2>" gilt nur als Operator am Wortanfang, sonst gehört die Ziffer zum Wort
+            if !(op.starts_with('2') && in_word) {
+                flush_word(&mut current, &mut in_word, &mut tokens);
+                tokens.push(Token { text: op.to_string(), kind: TokenKind::Operator });
+                i += op.chars().count();
+                continue;
+            }
+        }
+        in_word = true;
+        match c {
+            '\'' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                    current.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                current.push(chars[i]);
+            }
+            _ => current.push(c),
+        }
+        i += 1;
+    }
+    flush_word(&mut current, &mut in_word, &mut tokens);
+    tokens
+}
+
+// Wörter einer Zeile nach Shell-Regeln, ohne Anführungszeichen
+pub fn split_words(line: &str) -> Vec<String> {
+    tokenize(line).into_iter().map(|token| token.text).collect()
+}
+
+// Programmnamen aller Befehle einer Zeile, z. B. "FOO=1 ls | sort" -> ["ls", "sort"].
+// Schaut durch Subshells, Schlüsselwörter und Wrapper wie `xargs rm` hindurch und untersucht auch
+// Befehlsersetzungen und die Skripte hinter `bash -c`, damit die Sandbox nichts übersieht.
+pub fn program_names(line: &str) -> Vec<String> {
+    let mut programs = Vec::new();
+    let mut expect_program = true;
+    let mut skip_next = false;
+    // Nach einem Wrapper werden dessen Optionen und Zahlen (nice -n 10, timeout 5) übersprungen
+    let mut after_wrapper = false;
+    let mut script_next = false;
+    let mut current = String::new();
+    for token in tokenize(line) {
+        if token.kind == TokenKind::Operator {
+            if is_redirection(&token.text) {
+                skip_next = true;
+            } else {
+                expect_program = true;
+                after_wrapper = false;
+                script_next = false;
+                current.clear();
+            }
+            continue;
+        }
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        // Befehlsersetzung in Anführungszeichen: "$(rm x)"
+        if token.text.contains("$(") || token.text.contains('`') {
+            programs.extend(program_names(&token.text));
+        }
+        if script_next {
+            script_next = false;
+            programs.extend(program_names(&token.text));
+            continue;
+        }
+        if !expect_program {
+            if SHELLS.contains(&current.as_str()) && is_script_flag(&token.text) {
+                script_next = true;
+            } else if current == "find" && FIND_EXEC.contains(&token.text.as_str()) {
+                expect_program = true;
+            }
+            continue;
+        }
+        let word = token.text.trim_matches(|c| matches!(c, '{' | '}'));
+        if after_wrapper && word.starts_with('-') {
+            skip_next = wrapper_option_takes_value(&current, word);
+            continue;
+        }
+        let skipped_argument = after_wrapper && (word.starts_with('-') || word.starts_with(|c: char| c.is_ascii_digit()));
+        if word.is_empty() || SHELL_KEYWORDS.contains(&word) || is_assignment(word) || skipped_argument {
+            continue;
+        }
+        // eval "rm -rf x" oder xargs sh -c '...': ein ganzes Skript an der Stelle des Programms
+        if word.contains(char::is_whitespace) {
+            programs.extend(program_names(word));
+            expect_program = false;
+            continue;
+        }
+        programs.push(word.to_string());
+        current = word.rsplit('/').next().unwrap_or(word).to_string();
+        after_wrapper = WRAPPERS.contains(&current.as_str());
+        expect_program = after_wrapper;
+    }
+    programs
+}
+
+fn wrapper_option_takes_value(wrapper: &str, option: &str) -> bool {
+    WRAPPER_VALUE_OPTIONS
+        .iter()
+        .any(|(name, options)| *name == wrapper && options.contains(&option))
+}
+
+// "-c", aber auch zusammengefasst wie "-lc" oder "-ec"
+fn is_script_flag(word: &str) -> bool {
+    word.strip_prefix('-')
+        .is_some_and(|flags| !flags.starts_with('-') && flags.contains('c') && flags.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+fn operator_text(operator: &str) -> &'static str {
+    match operator {
+        "|" => "Pipe: die Ausgabe wird zur Eingabe des nächsten Befehls",
+        "||" => "Der nächste Befehl läuft nur, wenn dieser fehlschlägt",
+        "&&" => "Der nächste Befehl läuft nur, wenn dieser erfolgreich war",
+        ";" => "Befehle nacheinander ausführen",
+        "&" => "Befehl im Hintergrund ausführen",
+        ">" => "Standardausgabe in eine Datei schreiben (überschreiben)",
+        ">>" => "Standardausgabe an eine Datei anhängen",
+        "<" => "Standardeingabe aus einer Datei lesen",
+        "2>" => "Fehlerausgabe in eine Datei schreiben",
+        "2>>" => "Fehlerausgabe an eine Datei anhängen",
+        "2>&1" => "Fehlerausgabe auf die Standardausgabe umleiten",
+        "&>" => "Standard- und Fehlerausgabe in eine Datei schreiben",
+        "&>>" => "Standard- und Fehlerausgabe an eine Datei anhängen",
+        ">&" => "Standardausgabe auf einen anderen Dateideskriptor umleiten (z. B. >&2)",
+        "2>&" => "Fehlerausgabe auf einen anderen Dateideskriptor umleiten",
+        "<&" => "Standardeingabe von einem anderen Dateideskriptor lesen",
+        "(" => "Subshell: die Befehle laufen in einer eigenen Shell",
+        ")" => "Ende der Subshell bzw. Befehlsersetzung",
+        "$(" => "Befehlsersetzung: die Ausgabe wird an dieser Stelle eingesetzt",
+        "`" => "Befehlsersetzung (alte Schreibweise)",
+        _ => "Shell-Operator",
+    }
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.contains('=') && !word.starts_with('=') && !word.starts_with('-')
+}
+
+fn is_redirection(operator: &str) -> bool {
+    matches!(operator, ">" | ">>" | "<" | "2>" | "2>>" | "&>" | "&>>" | ">&" | "<&" | "2>&")
+}
+
+// Die Optionslisten werden pro Programm zwischengespeichert, damit man nicht bei jeder Erklärung erneut gestartet wird.
+// `man` läuft im Hintergrund; ist eine Liste da, erklärt `poll` die Zeile erneut.
+#[derive(Default)]
+pub struct Explainer {
+    options: HashMap<String, Option<Vec<ManOption>>>,
+    loading: HashMap<String, Receiver<Option<Vec<ManOption>>>>,
+}
+
+impl Explainer {
+    fn options_for(&mut self, program: &str) -> Option<&Vec<ManOption>> {
+        if !self.options.contains_key(program) && !self.loading.contains_key(program) {
+            let (sender, receiver) = mpsc::channel();
+            let name = program.to_string();
+            thread::spawn(move || {
+                debug!("Loading options of {} for explain", name);
+                let _ = sender.send(get_manpage(&name).ok().map(|manpage| parse_options(&manpage)));
+            });
+            self.loading.insert(program.to_string(), receiver);
+        }
+        self.options.get(program).and_then(Option::as_ref)
+    }
+
+    pub fn is_loading(&self) -> bool {
+        !self.loading.is_empty()
+    }
+
+    // true, wenn neue Optionslisten angekommen sind
+    pub fn poll(&mut self) -> bool {
+        let finished: Vec<(String, Option<Vec<ManOption>>)> = self.loading
+            .iter()
+            .filter_map(|(program, receiver)| receiver.try_recv().ok().map(|options| (program.clone(), options)))
+            .collect();
+        for (program, options) in &finished {
+            self.loading.remove(program);
+            self.options.insert(program.clone(), options.clone());
+        }
+        !finished.is_empty()
+    }
+
+    pub fn explain(&mut self, line: &str) -> Vec<ExplainedCommand> {
+        let mut commands: Vec<ExplainedCommand> = Vec::new();
+        let mut current: Option<ExplainedCommand> = None;
+        let mut tokens = tokenize(line).into_iter().peekable();
+        let mut pending_argument: Option<String> = None;
+
+        while let Some(token) = tokens.next() {
+            if token.kind == TokenKind::Operator {
+                let explanation = Explanation {
+                    token: token.text.clone(),
+                    text: operator_text(&token.text).to_string(),
+                    known: true,
+                };
+                let target = current.get_or_insert_with(|| ExplainedCommand {
+                    program: String::new(),
+                    has_manpage: false,
+                    explanations: Vec::new(),
+                });
+                target.explanations.push(explanation);
+                if is_redirection(&token.text) {
+                    if let Some(file) = tokens.next_if(|t| t.kind == TokenKind::Word) {
+                        target.explanations.push(Explanation { token: file.text, text: "Datei der Umleitung".to_string(), known: true });
+                    }
+                } else if let Some(command) = current.take() {
+                    commands.push(command);
+                }
+                pending_argument = None;
+                continue;
+            }
+
+            let Some(command) = current.as_mut().filter(|c| !c.program.is_empty()) else {
+                // Führende Variablenzuweisungen (FOO=bar cmd) gehören noch nicht zum Programm
+                if is_assignment(&token.text) {
+                    let target = current.get_or_insert_with(|| ExplainedCommand {
+                        program: String::new(),
+                        has_manpage: false,
+                        explanations: Vec::new(),
+                    });
+                    target.explanations.push(Explanation { token: token.text, text: "Umgebungsvariable für diesen Befehl".to_string(), known: true });
+                    continue;
+                }
+                let has_manpage = self.options_for(&token.text).is_some();
+                let loading = self.loading.contains_key(&token.text);
+                let mut command = current.take().unwrap_or(ExplainedCommand {
+                    program: String::new(),
+                    has_manpage: false,
+                    explanations: Vec::new(),
+                });
+                command.program = token.text.clone();
+                command.has_manpage = has_manpage;
+                command.explanations.push(Explanation {
+                    token: token.text,
+                    text: if has_manpage {
+                        "Programm".to_string()
+                    } else if loading {
+                        "Programm (Manpage wird geladen …)".to_string()
+                    } else {
+                        "Programm (keine Manpage gefunden)".to_string()
+                    },
+                    known: has_manpage || loading,
+                });
+                current = Some(command);
+                continue;
+            };
+
+            if let Some(option) = pending_argument.take() {
+                command.explanations.push(Explanation { token: token.text, text: format!("Wert für {}", option), known: true });
+                continue;
+            }
+
+            let program = command.program.clone();
+            let options = self.options_for(&program).cloned().unwrap_or_default();
+            let (explanations, takes_next) = explain_argument(&token.text, &options);
+            command.explanations.extend(explanations);
+            pending_argument = takes_next;
+        }
+        if let Some(command) = current {
+            commands.push(command);
+        }
+        commands
+    }
+}
+
+// Liefert die Erklärungen für ein Argument und ggf. die Option, deren Wert das nächste Wort ist
+fn explain_argument(token: &str, options: &[ManOption]) -> (Vec<Explanation>, Option<String>) {
+    let describe = |option: &ManOption| {
+        if option.description.is_empty() {
+            option.display_flags()
+        } else {
+            option.description.clone()
+        }
+    };
+
+    if token == "--" || !token.starts_with('-') || token == "-" {
+        let text = if token == "--" { "Ende der Optionen" } else { "Argument" };
+        return (vec![Explanation { token: token.to_string(), text: text.to_string(), known: token == "--" }], None);
+    }
+
+    // Ganze Option, auch bei einstelligen Strichen wie "-name" (find) oder "--width=80"
+    let (flag, inline_value) = match token.split_once('=') {
+        Some((flag, value)) => (flag, Some(value)),
+        None => (token, None),
+    };
+    if let Some(option) = options.iter().find(|o| o.matches(flag)) {
+        let takes_next = (option.argument.is_some() && !option.optional_argument && inline_value.is_none())
+            .then(|| flag.to_string());
+        return (vec![Explanation { token: token.to_string(), text: describe(option), known: true }], takes_next);
+    }
+
+    // Zusammengefasste Kurzoptionen wie -xzvf: der Rest nach einer Option mit Wert ist deren Wert
+    if !token.starts_with("--") {
+        let mut explanations = Vec::new();
+        let chars: Vec<char> = token[1..].chars().collect();
+        for (index, c) in chars.iter().enumerate() {
+            let short = format!("-{}", c);
+            match options.iter().find(|o| o.matches(&short)) {
+                Some(option) => {
+                    explanations.push(Explanation { token: short.clone(), text: describe(option), known: true });
+                    if option.argument.is_some() && !option.optional_argument {
+                        let rest: String = chars[index + 1..].iter().collect();
+                        if rest.is_empty() {
+                            return (explanations, Some(short));
+                        }
+                        explanations.push(Explanation { token: rest, text: format!("Wert für {}", short), known: true });
+                        return (explanations, None);
+                    }
+                }
+                None => explanations.push(Explanation { token: short, text: "Unbekannte Option".to_string(), known: false }),
+            }
+        }
+        if explanations.iter().any(|e| e.known) {
+            return (explanations, None);
+        }
+    }
+
+    (vec![Explanation { token: token.to_string(), text: "Unbekannte Option".to_string(), known: false }], None)
+}
+
+impl MyApp {
+    pub fn explain_command_line(&mut self) {
+        let line = self.explain_input.clone();
+        self.explain_result = self.explainer.explain(&line);
+    }
+
+    pub fn poll_explainer(&mut self) {
+        if self.explainer.poll() {
+            self.explain_command_line();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(flags: &[&str], argument: Option<&str>, description: &str) -> ManOption {
+        ManOption {
+            flags: flags.iter().map(|flag| flag.to_string()).collect(),
+            argument: argument.map(str::to_string),
+            optional_argument: false,
+            description: description.to_string(),
+        }
+    }
+
+    fn tar_options() -> Vec<ManOption> {
+        vec![
+            option(&["-x", "--extract"], None, "extract files"),
+            option(&["-z", "--gzip"], None, "filter through gzip"),
+            option(&["-v", "--verbose"], None, "verbosely list files"),
+            option(&["-f", "--file"], Some("ARCHIVE"), "use archive file"),
+            option(&["-C", "--directory"], Some("DIR"), "change to DIR"),
+            option(&["--exclude"], Some("PATTERN"), "exclude files"),
+        ]
+    }
+
+    fn pairs(explanations: &[Explanation]) -> Vec<(&str, &str)> {
+        explanations.iter().map(|e| (e.token.as_str(), e.text.as_str())).collect()
+    }
+
+    #[test]
+    fn tokenize_quotes_escapes_and_operators() {
+        let tokens = tokenize("grep -e 'a b' \"c\\\"d\" x\\ y 2>/dev/null|sort>>out && echo $(date)");
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, ["grep", "-e", "a b", "c\"d", "x y", "2>", "/dev/null", "|", "sort", ">>", "out", "&&", "echo", "$(", "date", ")"]);
+        let operators: Vec<&str> = tokens.iter().filter(|t| t.kind == TokenKind::Operator).map(|t| t.text.as_str()).collect();
+        assert_eq!(operators, ["2>", "|", ">>", "&&", "$(", ")"]);
+        // "2>" mitten im Wort ist kein Operator
+        assert_eq!(split_words("md2>x"), ["md2", ">", "x"]);
+    }
+
+    #[test]
+    fn program_names_of_pipelines_and_wrappers() {
+        assert_eq!(program_names("tar -xzvf foo.tgz -C /tmp"), ["tar"]);
+        assert_eq!(program_names("FOO=1 ls -l | sort -n > out.txt; wc -l"), ["ls", "sort", "wc"]);
+        assert_eq!(program_names("sudo -u root env LANG=C make install"), ["sudo", "env", "make"]);
+        assert_eq!(program_names("bash -c 'cd /tmp && ls'"), ["bash", "cd", "ls"]);
+        assert_eq!(program_names("echo \"$(date)\""), ["echo", "date"]);
+    }
+
+    #[test]
+    fn arguments_long_options_and_combined_flags() {
+        let options = tar_options();
+        let (explanations, takes_next) = explain_argument("-xzvf", &options);
+        assert_eq!(pairs(&explanations), [
+            ("-x", "extract files"),
+            ("-z", "filter through gzip"),
+            ("-v", "verbosely list files"),
+            ("-f", "use archive file"),
+        ]);
+        assert_eq!(takes_next.as_deref(), Some("-f"));
+
+        let (explanations, takes_next) = explain_argument("-fout.tar", &options);
+        assert_eq!(pairs(&explanations), [("-f", "use archive file"), ("out.tar", "Wert für -f")]);
+        assert_eq!(takes_next, None);
+
+        let (explanations, takes_next) = explain_argument("--exclude=*.o", &options);
+        assert_eq!(pairs(&explanations), [("--exclude=*.o", "exclude files")]);
+        assert_eq!(takes_next, None);
+        assert_eq!(explain_argument("--directory", &options).1.as_deref(), Some("--directory"));
+
+        assert!(!explain_argument("--nope", &options).0[0].known);
+        assert_eq!(pairs(&explain_argument("--", &options).0), [("--", "Ende der Optionen")]);
+        assert_eq!(pairs(&explain_argument("foo.tgz", &options).0), [("foo.tgz", "Argument")]);
+    }
+
+    #[test]
+    fn explains_the_readme_example() {
+        let mut explainer = Explainer::default();
+        explainer.options.insert("tar".to_string(), Some(tar_options()));
+        explainer.options.insert("sort".to_string(), None);
+        let commands = explainer.explain("tar -xzvf foo.tgz -C /tmp | sort > list");
+        assert_eq!(commands.len(), 2);
+        assert!(commands[0].has_manpage);
+        assert_eq!(pairs(&commands[0].explanations), [
+            ("tar", "Programm"),
+            ("-x", "extract files"),
+            ("-z", "filter through gzip"),
+            ("-v", "verbosely list files"),
+            ("-f", "use archive file"),
+            ("foo.tgz", "Wert für -f"),
+            ("-C", "change to DIR"),
+            ("/tmp", "Wert für -C"),
+            ("|", "Pipe: die Ausgabe wird zur Eingabe des nächsten Befehls"),
+        ]);
+        assert_eq!(commands[1].program, "sort");
+        assert!(!commands[1].has_manpage);
+        assert_eq!(commands[1].explanations[1].token, ">");
+        assert_eq!(pairs(&commands[1].explanations)[2], ("list", "Datei der Umleitung"));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::app::MyApp;
+use crate::config;
+use eframe::egui;
+
+const FAVORITES_FILE: &str = "cli_organizer_favorites.json";
+
+impl MyApp {
+    pub fn is_favorite(&self, command: &str) -> bool {
+        self.favorites.iter().any(|favorite| favorite == command)
+    }
+
+    pub fn toggle_favorite(&mut self, command: &str) {
+        if self.is_favorite(command) {
+            self.favorites.retain(|favorite| favorite != command);
+        } else {
+            self.favorites.push(command.to_string());
+        }
+        self.save_favorites();
+    }
+
+    // Favoriten können auch komplette Befehlszeilen aus dem Builder sein: dann wird das Programm
+    // geöffnet und die Zeile kopiert. Unterbefehle wie "git rebase" sind dagegen eigene Einträge.
+    pub fn open_favorite(&mut self, entry: &str, ctx: &egui::Context) {
+        let is_subcommand = entry.split_once(' ').is_some_and(|(parent, sub)| {
+            self.subcommands.children.get(parent).is_some_and(|children| children.iter().any(|child| child == sub))
+        });
+        let program = if is_subcommand { entry } else { entry.split_whitespace().next().unwrap_or(entry) }.to_string();
+        self.set_search_query(String::new());
+        self.select_command(&program);
+        if program != entry {
+            self.copy_example(ctx, entry);
+        }
+    }
+
+    pub fn load_favorites(&mut self) {
+        if let Some(favorites) = config::load_json(FAVORITES_FILE) {
+            self.favorites = favorites;
+        }
+    }
+
+    pub fn save_favorites(&self) {
+        config::save_json(FAVORITES_FILE, &self.favorites);
+    }
+}
@@ -0,0 +1,350 @@
+use crate::config;
+use crate::manpage::{body_indent, is_examples_section, is_section_heading, page_name_and_section};
+use eframe::egui;
+use egui::{text::{CCursor, LayoutJob}, Color32, FontId, Galley, TextFormat};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use log::{debug, error};
+use std::fs;
+use std::sync::Arc;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+pub const THEME_KEY: &str = "cli_organizer_theme";
+const CUSTOM_THEMES_DIR: &str = "cli_organizer_themes";
+// Hauptbereich und geteilte Ansicht, jeweils auch kurz nach einer Größenänderung
+const LAYOUT_CACHE_SIZE: usize = 4;
+
+// Grammatik, mit der eine einzelne Zeile der Manpage hervorgehoben wird
+#[derive(Clone, Copy, PartialEq)]
+enum CodeKind {
+    Plain,
+    Shell,
+    C,
+    Config,
+    Json,
+}
+
+#[derive(PartialEq)]
+struct LayoutKey {
+    page_hash: u64,
+    filter: String,
+    theme: String,
+    width_bits: u32,
+    pixels_per_point_bits: u32,
+}
+
+struct CachedLayout {
+    key: LayoutKey,
+    layout: ManpageLayout,
+}
+
+#[derive(Clone)]
+pub struct ManpageLayout {
+    pub galley: Arc<Galley>,
+    // Zeichenposition jeder angezeigten Zeile im Galley, zuletzt das Textende
+    line_offsets: Arc<[usize]>,
+    // Überschriften der angezeigten Zeilen mit ihrer Höhe im Galley
+    pub sections: Vec<(String, f32)>,
+}
+
+impl ManpageLayout {
+    // Bereich der angezeigten Zeile `index` über die volle Breite, auch wenn sie umbricht.
+    // Der aktuelle Treffer wird damit hinterlegt, statt das Layout für jeden Treffer neu zu berechnen.
+    pub fn line_rect(&self, index: usize) -> Option<egui::Rect> {
+        let start = *self.line_offsets.get(index)?;
+        let end = *self.line_offsets.get(index + 1)?;
+        let top = self.galley.pos_from_ccursor(CCursor::new(start));
+        let bottom = self.galley.pos_from_ccursor(CCursor::new(end.saturating_sub(1).max(start)));
+        Some(egui::Rect::from_x_y_ranges(self.galley.rect.x_range(), top.min.y..=bottom.max.y))
+    }
+
+    // Letzte Überschrift oberhalb von `offset` (plus einer Zeile Spielraum)
+    pub fn section_at(&self, offset: f32, line_height: f32) -> Option<&str> {
+        self.sections
+            .iter()
+            .take_while(|(_, y)| *y <= offset + line_height)
+            .last()
+            .map(|(title, _)| title.as_str())
+    }
+}
+
+// Syntax- und Theme-Sets werden einmal geladen; das fertige Layout wird nur bei Änderungen neu berechnet
+pub struct Highlighter {
+    syntax_set: SyntaxSet,
+    theme_set: ThemeSet,
+    // Zuletzt benutzte Layouts zuerst
+    cache: Vec<CachedLayout>,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        let mut theme_set = ThemeSet::load_defaults();
+        load_custom_themes(&mut theme_set);
+        Self {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme_set,
+            cache: Vec::new(),
+        }
+    }
+}
+
+// Eigene Themes: *.tmTheme-Dateien im Ordner cli_organizer_themes des Konfigurationsverzeichnisses
+fn load_custom_themes(theme_set: &mut ThemeSet) {
+    let Some(dir) = config::config_path(CUSTOM_THEMES_DIR) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("tmTheme") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        match ThemeSet::get_theme(&path) {
+            Ok(theme) => {
+                debug!("Loaded custom theme {}", name);
+                theme_set.themes.insert(name.to_string(), theme);
+            }
+            Err(e) => error!("Failed to load theme {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Highlighter {
+    pub fn theme_names(&self) -> Vec<String> {
+        self.theme_set.themes.keys().cloned().collect()
+    }
+
+    pub fn layout(
+        &mut self,
+        ui: &egui::Ui,
+        manpage: &str,
+        filter: &str,
+        theme: &str,
+    ) -> ManpageLayout {
+        let mut hasher = DefaultHasher::new();
+        manpage.hash(&mut hasher);
+        let key = LayoutKey {
+            page_hash: hasher.finish(),
+            filter: filter.to_string(),
+            theme: theme.to_string(),
+            width_bits: ui.available_width().to_bits(),
+            pixels_per_point_bits: ui.ctx().pixels_per_point().to_bits(),
+        };
+
+        if let Some(index) = self.cache.iter().position(|cached| cached.key == key) {
+            let cached = self.cache.remove(index);
+            let layout = cached.layout.clone();
+            self.cache.insert(0, cached);
+            return layout;
+        }
+
+        let (job, line_offsets, heading_offsets) = self.build_job(&key, manpage, ui.available_width());
+        let galley = ui.fonts(|f| f.layout_job(job));
+        let sections = heading_offsets
+            .into_iter()
+            .map(|(title, offset)| (title, galley.pos_from_ccursor(CCursor::new(offset)).min.y))
+            .collect();
+        let layout = ManpageLayout { galley, line_offsets: line_offsets.into(), sections };
+        self.cache.insert(0, CachedLayout { key, layout: layout.clone() });
+        self.cache.truncate(LAYOUT_CACHE_SIZE);
+        layout
+    }
+
+    fn build_job(&self, key: &LayoutKey, manpage: &str, width: f32) -> (LayoutJob, Vec<usize>, Vec<(String, usize)>) {
+        let theme = self.theme_set.themes.get(&key.theme).unwrap_or(&self.theme_set.themes[DEFAULT_THEME]);
+        let (page_name, man_section) = page_name_and_section(manpage).unwrap_or_default();
+        let body_indent = body_indent(manpage);
+        let kinds = line_kinds(manpage, &man_section, body_indent);
+        let mut section_title = String::new();
+        let mut highlighter: Option<(CodeKind, HighlightLines)> = None;
+
+        let mut job = LayoutJob::default();
+        let mut line_offsets = Vec::new();
+        let mut heading_offsets = Vec::new();
+        let mut char_offset = 0;
+        for (line, kind) in LinesWithEndings::from(manpage).zip(kinds) {
+            if is_section_heading(line) {
+                section_title = line.trim().to_string();
+            }
+            // Die Zeilenfilterung entspricht manpage::filter_lines, damit die Trefferindizes passen
+            if !key.filter.is_empty() && !line.contains(key.filter.as_str()) {
+                continue;
+            }
+
+            if highlighter.as_ref().map(|(current, _)| *current) != Some(kind) {
+                let syntax = self.syntax_for(kind, &page_name);
+                highlighter = Some((kind, HighlightLines::new(syntax, theme)));
+            }
+            let (_, h) = highlighter.as_mut().expect("highlighter was just set");
+
+            line_offsets.push(char_offset);
+            if is_section_heading(line) {
+                heading_offsets.push((section_title.clone(), char_offset));
+            }
+            char_offset += line.chars().count();
+
+            let ranges: Vec<(Style, &str)> = h.highlight_line(line, &self.syntax_set).unwrap_or_default();
+            for (style, text) in ranges {
+                let color = Color32::from_rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+                job.append(text, 0.0, TextFormat::simple(FontId::default(), color));
+            }
+        }
+        line_offsets.push(char_offset);
+        job.wrap.max_width = width;
+        (job, line_offsets, heading_offsets)
+    }
+
+    fn syntax_for(&self, kind: CodeKind, page_name: &str) -> &SyntaxReference {
+        let syntax = match kind {
+            CodeKind::Plain => None,
+            CodeKind::Shell => self.syntax_set.find_syntax_by_extension("sh"),
+            CodeKind::C => self.syntax_set.find_syntax_by_extension("c"),
+            CodeKind::Json => self.syntax_set.find_syntax_by_extension("json"),
+            // Konfigurationsformate anhand des Seitennamens raten (z. B. "yaml", "json"), sonst Schlüssel=Wert-Syntax
+            CodeKind::Config => page_name
+                .rsplit(['.', '_', '-'])
+                .find_map(|token| self.syntax_set.find_syntax_by_token(token))
+                .or_else(|| ["ini", "properties", "conf", "sh"]
+                    .iter()
+                    .find_map(|ext| self.syntax_set.find_syntax_by_extension(ext))),
+        };
+        syntax.unwrap_or_else(|| self.syntax_set.find_syntax_plain_text())
+    }
+}
+
+// Grammatik jeder Zeile: feste Abschnitte wie SYNOPSIS und EXAMPLES über line_kind,
+// tiefer eingerückte Blöcke im übrigen Text anhand ihres Inhalts
+fn line_kinds(manpage: &str, man_section: &str, body_indent: usize) -> Vec<CodeKind> {
+    let lines: Vec<&str> = LinesWithEndings::from(manpage).collect();
+    let mut section_title = String::new();
+    let mut kinds: Vec<CodeKind> = lines
+        .iter()
+        .map(|line| {
+            if is_section_heading(line) {
+                section_title = line.trim().to_string();
+            }
+            line_kind(&section_title, man_section, line, body_indent)
+        })
+        .collect();
+
+    let block_lines: Vec<bool> = lines
+        .iter()
+        .zip(&kinds)
+        .map(|(line, kind)| *kind == CodeKind::Plain && is_indented_block_line(line, body_indent))
+        .collect();
+    // Durch Leerzeilen getrennte Teile eines Codeblocks behalten dessen Grammatik
+    let mut previous_code = None;
+    let mut start = 0;
+    while start < lines.len() {
+        if !block_lines[start] {
+            if !lines[start].trim().is_empty() {
+                previous_code = None;
+            }
+            start += 1;
+            continue;
+        }
+        let end = (start..lines.len()).find(|&index| !block_lines[index]).unwrap_or(lines.len());
+        let kind = block_kind(&lines[start..end], man_section, previous_code);
+        previous_code = (kind != CodeKind::Plain).then_some(kind);
+        kinds[start..end].fill(kind);
+        start = end;
+    }
+    kinds
+}
+
+fn line_kind(section_title: &str, man_section: &str, line: &str, body_indent: usize) -> CodeKind {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || is_section_heading(line) {
+        return CodeKind::Plain;
+    }
+    let indent = line.len() - line.trim_start().len();
+    let is_c_page = man_section.starts_with('2') || man_section.starts_with('3');
+    let is_config_page = man_section.starts_with('5');
+
+    // Zeilen mit Prompt sind fast immer Beispielbefehle
+    if trimmed.starts_with("$ ") || trimmed.starts_with("% ") {
+        return CodeKind::Shell;
+    }
+    match section_title {
+        "ALIAS" | "FUNCTION" => CodeKind::Shell,
+        "SYNOPSIS" if is_c_page => CodeKind::C,
+        "SYNOPSIS" => CodeKind::Shell,
+        title if is_examples_section(title) && indent > body_indent => {
+            if is_config_page {
+                CodeKind::Config
+            } else if is_c_page {
+                CodeKind::C
+            } else {
+                CodeKind::Shell
+            }
+        }
+        _ => CodeKind::Plain,
+    }
+}
+
+fn is_indented_block_line(line: &str, body_indent: usize) -> bool {
+    let indent = line.len() - line.trim_start().len();
+    !line.trim().is_empty() && indent >= body_indent + 4
+}
+
+// Eingerückter Text ist meist die Beschreibung einer Option; Code wird am Inhalt erkannt, im Zweifel Shell
+fn block_kind(block: &[&str], man_section: &str, previous_code: Option<CodeKind>) -> CodeKind {
+    let lines: Vec<&str> = block.iter().map(|line| line.trim()).collect();
+    if lines.iter().filter(|line| is_prose(line)).count() * 2 > lines.len() {
+        return CodeKind::Plain;
+    }
+    let is_c_page = man_section.starts_with('2') || man_section.starts_with('3');
+    if is_c_page || lines.iter().any(|line| is_c_line(line)) {
+        CodeKind::C
+    } else if (lines[0].starts_with('{') || lines[0] == "[") && lines.iter().any(|line| line.contains("\":")) {
+        CodeKind::Json
+    } else if man_section.starts_with('5') || is_config_block(&lines) {
+        CodeKind::Config
+    } else {
+        previous_code.unwrap_or(CodeKind::Shell)
+    }
+}
+
+// Fließtext: viele Wörter aus Buchstaben oder ein Satzende, aber keine Shell- oder Code-Zeichen
+fn is_prose(line: &str) -> bool {
+    if ["&&", "||", " | ", "$", "=", "{", "}", "<", ">", "`"].iter().any(|symbol| line.contains(symbol)) {
+        return false;
+    }
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let is_word = |word: &str| {
+        let word = word.trim_start_matches(['(', '"']).trim_end_matches([',', '.', ';', ':', '!', '?', ')', '"']);
+        !word.is_empty() && word.chars().all(|c| c.is_alphabetic() || c == '-' || c == '\'')
+    };
+    let alphabetic = words.iter().filter(|word| is_word(word)).count();
+    let ends_sentence = words.len() >= 2
+        && words.last().is_some_and(|last| last.ends_with(['.', ':']) && is_word(last));
+    (words.len() >= 5 && alphabetic * 5 >= words.len() * 4) || ends_sentence
+}
+
+fn is_c_line(line: &str) -> bool {
+    line.starts_with("#include")
+        || line.starts_with("#define")
+        || (line.ends_with(';') && !line.ends_with("\\;") && line.contains('('))
+        || (line.ends_with('{') && line.contains(')'))
+}
+
+// Nur Kommentare, [Abschnitte] und Schlüssel=Wert, mit mindestens einem eindeutigen Merkmal
+fn is_config_block(lines: &[&str]) -> bool {
+    let is_section = |line: &&str| line.starts_with('[') && line.ends_with(']');
+    let is_assignment = |line: &str| {
+        line.split_once('=').is_some_and(|(key, _)| {
+            let key = key.trim();
+            !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        })
+    };
+    lines.iter().all(|line| line.starts_with(['#', ';']) || is_section(line) || is_assignment(line))
+        && lines.iter().any(|line| is_section(line) || line.contains(" = "))
+}
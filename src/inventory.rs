@@ -0,0 +1,413 @@
+use crate::cli::command_path;
+use crate::manpage::installed_pages;
+use crate::package::PackageIndex;
+use crate::version::{binary_mtime, is_probe_allowed, probe, CachedVersion, VersionRequest};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Versionsabfragen warten oft auf ein Timeout, daher mehrere gleichzeitig
+const PROBE_WORKERS: usize = 8;
+const CSV_COLUMNS: [&str; 8] = ["command", "path", "package", "package_manager", "package_version", "version", "semver", "manpage"];
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub command: String,
+    pub path: Option<String>,
+    pub package: Option<String>,
+    pub package_manager: Option<String>,
+    pub package_version: Option<String>,
+    // Erste Zeile der Ausgabe von --version o. Ä.
+    pub version: Option<String>,
+    pub semver: Option<String>,
+    pub manpage: bool,
+}
+
+impl CatalogEntry {
+    // Zum Vergleichen nur Gleichartiges: die normalisierten Versionen, sonst die Rohausgaben, sonst die
+    // Paketversionen, jeweils nur wenn beide Seiten sie haben ("2.39.5" gegen "git version 2.39.5" wäre kein Unterschied)
+    pub fn comparable_versions<'a>(&'a self, other: &'a CatalogEntry) -> Option<(&'a str, &'a str)> {
+        [
+            (&self.semver, &other.semver),
+            (&self.version, &other.version),
+            (&self.package_version, &other.package_version),
+        ]
+        .into_iter()
+        .find_map(|(own, other)| Some((own.as_deref()?, other.as_deref()?)))
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Catalog {
+    pub host: String,
+    // Sekunden seit 1970
+    pub created_at: u64,
+    pub entries: Vec<CatalogEntry>,
+}
+
+pub enum CatalogProgress {
+    Step(usize, usize),
+    // Der Katalog und die dabei ermittelten Versionen für den Cache
+    Done(Catalog, HashMap<String, CachedVersion>),
+}
+
+// Programme ohne gültigen Eintrag im Versions-Cache; nur diese würde eine Live-Abfrage starten
+pub fn probe_candidates(
+    commands: &[String],
+    versions: &HashMap<String, CachedVersion>,
+    version_subcommands: &HashSet<String>,
+) -> Vec<VersionRequest> {
+    commands
+        .iter()
+        .filter(|command| is_probe_allowed(command))
+        .filter_map(|command| command_path(command).map(|path| (command, path)))
+        .filter(|(command, path)| {
+            let mtime = binary_mtime(path).unwrap_or(0);
+            !versions.get(*command).is_some_and(|cached| &cached.path == path && cached.mtime == mtime)
+        })
+        .map(|(command, path)| VersionRequest {
+            command: command.clone(),
+            has_version_subcommand: version_subcommands.contains(command),
+            path,
+        })
+        .collect()
+}
+
+// Sammelt Pfad, Paket, Version und Manpage für jeden Befehl. Versionen stammen aus dem Cache und den Paketen;
+// gestartet werden nur die Programme in `to_probe`, die der Benutzer ausdrücklich freigegeben hat.
+pub fn build_catalog(
+    commands: &[String],
+    mut versions: HashMap<String, CachedVersion>,
+    to_probe: Vec<VersionRequest>,
+    progress: &Sender<CatalogProgress>,
+) -> (Catalog, HashMap<String, CachedVersion>) {
+    let manpages = installed_pages();
+    let paths: HashMap<&str, PathBuf> = commands
+        .iter()
+        .filter_map(|command| command_path(command).map(|path| (command.as_str(), path)))
+        .collect();
+
+    let total = to_probe.len();
+    let next = AtomicUsize::new(0);
+    let probed = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..PROBE_WORKERS {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(request) = to_probe.get(index) else {
+                    break;
+                };
+                let cached = CachedVersion {
+                    path: request.path.clone(),
+                    mtime: binary_mtime(&request.path).unwrap_or(0),
+                    version: probe(request),
+                };
+                let mut probed = probed.lock().unwrap_or_else(|e| e.into_inner());
+                probed.push((request.command.clone(), cached));
+                let _ = progress.send(CatalogProgress::Step(probed.len(), total));
+            });
+        }
+    });
+    versions.extend(probed.into_inner().unwrap_or_else(|e| e.into_inner()));
+
+    let mut index = PackageIndex::default();
+    let entries = commands
+        .iter()
+        .map(|command| {
+            let path = paths.get(command.as_str());
+            let package = path.and_then(|path| index.lookup(path));
+            let version = path
+                .and_then(|path| versions.get(command).filter(|cached| &cached.path == path))
+                .and_then(|cached| cached.version.as_ref());
+            CatalogEntry {
+                command: command.clone(),
+                path: path.map(|path| path.to_string_lossy().to_string()),
+                package_manager: package.as_ref().map(|package| package.manager.label().to_string()),
+                package_version: package.as_ref().and_then(|package| package.version.clone()),
+                package: package.map(|package| package.name),
+                version: version.map(|version| version.raw.clone()),
+                semver: version.and_then(|version| version.semver.clone()),
+                manpage: manpages.contains_key(command),
+            }
+        })
+        .collect();
+    debug!("Built catalog of {} commands, probed {} versions", commands.len(), total);
+    let catalog = Catalog { host: host_name(), created_at: now(), entries };
+    (catalog, versions)
+}
+
+fn host_name() -> String {
+    fs::read_to_string("/etc/hostname")
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "unbekannt".to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+}
+
+// Format nach Dateiendung: .csv, sonst JSON
+pub fn export(path: &Path, catalog: &Catalog) -> io::Result<()> {
+    let text = if is_csv(path) {
+        to_csv(&catalog.entries)
+    } else {
+        serde_json::to_string_pretty(catalog).map_err(io::Error::other)?
+    };
+    fs::write(path, text)
+}
+
+pub fn load(path: &Path) -> io::Result<Catalog> {
+    let text = fs::read_to_string(path)?;
+    if !is_csv(path) {
+        return serde_json::from_str(&text).map_err(io::Error::other);
+    }
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().map(parse_csv_line).unwrap_or_default();
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let Some(command_column) = column("command") else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Spalte \"command\" fehlt"));
+    };
+    let columns: Vec<Option<usize>> = CSV_COLUMNS.iter().map(|name| column(name)).collect();
+    let entries = lines
+        .map(parse_csv_line)
+        .map(|fields| {
+            let field = |index: usize| {
+                columns[index]
+                    .and_then(|column| fields.get(column))
+                    .filter(|value| !value.is_empty())
+                    .cloned()
+            };
+            CatalogEntry {
+                command: fields.get(command_column).cloned().unwrap_or_default(),
+                path: field(1),
+                package: field(2),
+                package_manager: field(3),
+                package_version: field(4),
+                version: field(5),
+                semver: field(6),
+                manpage: field(7).is_some_and(|value| value == "true" || value == "1"),
+            }
+        })
+        .collect();
+    // CSV enthält keinen Rechnernamen, stattdessen der Dateiname
+    let host = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let created_at = binary_mtime(path).unwrap_or(0);
+    Ok(Catalog { host, created_at, entries })
+}
+
+fn to_csv(entries: &[CatalogEntry]) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push('\n');
+    for entry in entries {
+        let fields = [
+            Some(entry.command.as_str()),
+            entry.path.as_deref(),
+            entry.package.as_deref(),
+            entry.package_manager.as_deref(),
+            entry.package_version.as_deref(),
+            entry.version.as_deref(),
+            entry.semver.as_deref(),
+            Some(if entry.manpage { "true" } else { "false" }),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field.unwrap_or_default())).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"").replace(['\n', '\r'], " "))
+    } else {
+        value.to_string()
+    }
+}
+
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[derive(Default)]
+pub struct CatalogDiff {
+    pub left_host: String,
+    pub right_host: String,
+    pub only_left: Vec<String>,
+    pub only_right: Vec<String>,
+    // (Befehl, links, rechts)
+    pub version_mismatches: Vec<(String, String, String)>,
+    pub path_differences: Vec<(String, String, String)>,
+}
+
+impl CatalogDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_left.is_empty()
+            && self.only_right.is_empty()
+            && self.version_mismatches.is_empty()
+            && self.path_differences.is_empty()
+    }
+}
+
+pub fn diff(left: &Catalog, right: &Catalog) -> CatalogDiff {
+    let left_entries: HashMap<&str, &CatalogEntry> = left.entries.iter().map(|entry| (entry.command.as_str(), entry)).collect();
+    let right_entries: HashMap<&str, &CatalogEntry> = right.entries.iter().map(|entry| (entry.command.as_str(), entry)).collect();
+    let mut diff = CatalogDiff {
+        left_host: left.host.clone(),
+        right_host: right.host.clone(),
+        ..CatalogDiff::default()
+    };
+    for (command, left_entry) in &left_entries {
+        let Some(right_entry) = right_entries.get(command) else {
+            diff.only_left.push(command.to_string());
+            continue;
+        };
+        if let Some((left_version, right_version)) = left_entry.comparable_versions(right_entry) {
+            if left_version != right_version {
+                diff.version_mismatches.push((command.to_string(), left_version.to_string(), right_version.to_string()));
+            }
+        }
+        if let (Some(left_path), Some(right_path)) = (&left_entry.path, &right_entry.path) {
+            if left_path != right_path {
+                diff.path_differences.push((command.to_string(), left_path.clone(), right_path.clone()));
+            }
+        }
+    }
+    diff.only_right = right_entries
+        .keys()
+        .filter(|command| !left_entries.contains_key(*command))
+        .map(|command| command.to_string())
+        .collect();
+    diff.only_left.sort();
+    diff.only_right.sort();
+    diff.version_mismatches.sort();
+    diff.path_differences.sort();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(command: &str, path: &str, version: Option<&str>) -> CatalogEntry {
+        CatalogEntry {
+            command: command.to_string(),
+            path: Some(path.to_string()),
+            semver: version.map(str::to_string),
+            ..CatalogEntry::default()
+        }
+    }
+
+    fn catalog(host: &str, entries: Vec<CatalogEntry>) -> Catalog {
+        Catalog { host: host.to_string(), created_at: 0, entries }
+    }
+
+    #[test]
+    fn csv_fields_with_quotes_and_commas() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(parse_csv_line("git,\"a,b\",\"say \"\"hi\"\"\",,true"), ["git", "a,b", "say \"hi\"", "", "true"]);
+    }
+
+    #[test]
+    fn csv_export_and_load_round_trip() {
+        let mut git = entry("git", "/usr/bin/git", Some("2.39.5"));
+        git.version = Some("git version 2.39.5, \"Debian\"".to_string());
+        git.package = Some("git".to_string());
+        git.manpage = true;
+        let path = std::env::temp_dir().join(format!("cli_organizer_inventory_{}.csv", std::process::id()));
+        export(&path, &catalog("host", vec![git, entry("ls", "/usr/bin/ls", None)])).unwrap();
+        let loaded = load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.entries.len(), 2);
+        let git = &loaded.entries[0];
+        assert_eq!(git.command, "git");
+        assert_eq!(git.version.as_deref(), Some("git version 2.39.5, \"Debian\""));
+        assert_eq!(git.semver.as_deref(), Some("2.39.5"));
+        assert_eq!(git.package.as_deref(), Some("git"));
+        assert!(git.manpage);
+        assert_eq!(loaded.entries[1].semver, None);
+        assert!(!loaded.entries[1].manpage);
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_name() {
+        let path = std::env::temp_dir().join(format!("cli_organizer_inventory_columns_{}.csv", std::process::id()));
+        fs::write(&path, "semver,command\n1.0.0,foo\n\n,bar\n").unwrap();
+        let loaded = load(&path).unwrap();
+        fs::write(&path, "name,semver\nfoo,1.0.0\n").unwrap();
+        let missing = load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[0].command, "foo");
+        assert_eq!(loaded.entries[0].semver.as_deref(), Some("1.0.0"));
+        assert_eq!(loaded.entries[1].semver, None);
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn diff_reports_missing_commands_versions_and_paths() {
+        let left = catalog("a", vec![
+            entry("git", "/usr/bin/git", Some("2.39.5")),
+            entry("jq", "/usr/bin/jq", Some("1.6.0")),
+            entry("ls", "/usr/bin/ls", None),
+            entry("vim", "/usr/bin/vim", Some("9.0.0")),
+        ]);
+        let right = catalog("b", vec![
+            entry("git", "/usr/local/bin/git", Some("2.45.0")),
+            entry("jq", "/usr/bin/jq", Some("1.6.0")),
+            entry("ls", "/usr/bin/ls", Some("9.1.0")),
+            entry("rg", "/usr/bin/rg", Some("14.1.0")),
+        ]);
+        let changes = diff(&left, &right);
+        assert_eq!(changes.only_left, ["vim"]);
+        assert_eq!(changes.only_right, ["rg"]);
+        assert_eq!(changes.version_mismatches, [("git".to_string(), "2.39.5".to_string(), "2.45.0".to_string())]);
+        assert_eq!(changes.path_differences, [("git".to_string(), "/usr/bin/git".to_string(), "/usr/local/bin/git".to_string())]);
+        assert!(!changes.is_empty());
+
+        // JSON mit semver gegen eine CSV ohne diese Spalte: nur die Rohausgaben vergleichen
+        let mut with_semver = entry("git", "/usr/bin/git", Some("2.39.5"));
+        with_semver.version = Some("git version 2.39.5".to_string());
+        let mut raw_only = entry("git", "/usr/bin/git", None);
+        raw_only.version = Some("git version 2.39.5".to_string());
+        assert!(diff(&catalog("a", vec![with_semver.clone()]), &catalog("b", vec![raw_only])).is_empty());
+        let mut package_only = entry("git", "/usr/bin/git", None);
+        package_only.package_version = Some("1:2.39.5-0+deb12u1".to_string());
+        assert!(diff(&catalog("a", vec![with_semver]), &catalog("b", vec![package_only])).is_empty());
+        assert!(diff(&left, &left).is_empty());
+    }
+}
@@ -0,0 +1,411 @@
+use crate::app::{MyApp, Pane};
+use crate::config;
+use eframe::egui;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const KEYBINDINGS_FILE: &str = "cli_organizer_keybindings.json";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum Action {
+    FocusSearch,
+    FocusManpageSearch,
+    NextPane,
+    PreviousPane,
+    SelectNext,
+    SelectPrevious,
+    PageUp,
+    PageDown,
+    ScrollToTop,
+    ScrollToBottom,
+    NextMatch,
+    PreviousMatch,
+    OpenExample,
+    ToggleFavorite,
+    Back,
+    Forward,
+    ExplainCommand,
+    RunCommand,
+    Inventory,
+    PathCheck,
+    WhatsNew,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    SplitView,
+    CommandPalette,
+    ToggleHelp,
+}
+
+impl Action {
+    pub const ALL: [Action; 28] = [
+        Action::FocusSearch,
+        Action::FocusManpageSearch,
+        Action::NextPane,
+        Action::PreviousPane,
+        Action::SelectNext,
+        Action::SelectPrevious,
+        Action::PageUp,
+        Action::PageDown,
+        Action::ScrollToTop,
+        Action::ScrollToBottom,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::OpenExample,
+        Action::ToggleFavorite,
+        Action::Back,
+        Action::Forward,
+        Action::ExplainCommand,
+        Action::RunCommand,
+        Action::Inventory,
+        Action::PathCheck,
+        Action::WhatsNew,
+        Action::NewTab,
+        Action::CloseTab,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::SplitView,
+        Action::CommandPalette,
+        Action::ToggleHelp,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::FocusSearch => "Befehlssuche fokussieren",
+            Action::FocusManpageSearch => "Suche in Manpage fokussieren",
+            Action::NextPane => "Nächster Bereich",
+            Action::PreviousPane => "Vorheriger Bereich",
+            Action::SelectNext => "Nächster Befehl / Zeile runter",
+            Action::SelectPrevious => "Vorheriger Befehl / Zeile hoch",
+            Action::PageUp => "Manpage: Seite hoch",
+            Action::PageDown => "Manpage: Seite runter",
+            Action::ScrollToTop => "Manpage: zum Anfang",
+            Action::ScrollToBottom => "Manpage: zum Ende",
+            Action::NextMatch => "Nächster Treffer",
+            Action::PreviousMatch => "Vorheriger Treffer",
+            Action::OpenExample => "Beispiele öffnen",
+            Action::ToggleFavorite => "Favorit umschalten",
+            Action::Back => "Zurück",
+            Action::Forward => "Vorwärts",
+            Action::ExplainCommand => "Befehlszeile erklären",
+            Action::RunCommand => "Befehl ausprobieren",
+            Action::Inventory => "Inventar der Werkzeuge",
+            Action::PathCheck => "PATH-Diagnose",
+            Action::WhatsNew => "Neu auf diesem System",
+            Action::NewTab => "Manpage in neuem Tab",
+            Action::CloseTab => "Tab schließen",
+            Action::NextTab => "Nächster Tab",
+            Action::PreviousTab => "Vorheriger Tab",
+            Action::SplitView => "Geteilte Ansicht",
+            Action::CommandPalette => "Befehlspalette",
+            Action::ToggleHelp => "Tastenkürzel anzeigen",
+        }
+    }
+
+    fn default_bindings(self) -> &'static [&'static str] {
+        match self {
+            Action::FocusSearch => &["/", "Ctrl+F"],
+            Action::FocusManpageSearch => &["Ctrl+Shift+F"],
+            Action::NextPane => &["F6"],
+            Action::PreviousPane => &["Shift+F6"],
+            Action::SelectNext => &["Down"],
+            Action::SelectPrevious => &["Up"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::ScrollToTop => &["Home"],
+            Action::ScrollToBottom => &["End"],
+            Action::NextMatch => &["N"],
+            Action::PreviousMatch => &["Shift+N"],
+            Action::OpenExample => &["E"],
+            Action::ToggleFavorite => &["S"],
+            Action::Back => &["Alt+Left", "Backspace"],
+            Action::Forward => &["Alt+Right"],
+            Action::ExplainCommand => &["Ctrl+E"],
+            Action::RunCommand => &["Ctrl+R"],
+            Action::Inventory => &["Ctrl+I"],
+            Action::PathCheck => &["Ctrl+D"],
+            Action::WhatsNew => &["Ctrl+N"],
+            Action::NewTab => &["Ctrl+T"],
+            Action::CloseTab => &["Ctrl+W"],
+            Action::NextTab => &["Ctrl+PageDown"],
+            Action::PreviousTab => &["Ctrl+PageUp"],
+            Action::SplitView => &["Ctrl+Backslash"],
+            Action::CommandPalette => &["Ctrl+P"],
+            Action::ToggleHelp => &["F1", "?"],
+        }
+    }
+}
+
+// Format der Konfigurationsdatei: {"FocusSearch": ["/", "Ctrl+F"], ...}.
+// Nicht aufgeführte Aktionen behalten ihre Standardbelegung, eine leere Liste entfernt sie.
+pub struct Keymap {
+    bindings: Vec<(Action, KeyboardShortcut)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new())
+    }
+}
+
+impl Keymap {
+    pub fn load() -> Self {
+        match config::load_json::<BTreeMap<Action, Vec<String>>>(KEYBINDINGS_FILE) {
+            Some(overrides) => Self::from_config(&overrides),
+            None => {
+                let keymap = Self::default();
+                config::save_json(KEYBINDINGS_FILE, &keymap.to_config());
+                keymap
+            }
+        }
+    }
+
+    fn from_config(overrides: &BTreeMap<Action, Vec<String>>) -> Self {
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let texts: Vec<&str> = match overrides.get(&action) {
+                Some(texts) => texts.iter().map(String::as_str).collect(),
+                None => action.default_bindings().to_vec(),
+            };
+            for text in texts {
+                match parse_shortcut(text) {
+                    Some(shortcut) => bindings.push((action, shortcut)),
+                    None => warn!("Ignoring unknown key binding {:?} for {:?}", text, action),
+                }
+            }
+        }
+        // Kürzel mit mehr Modifikatoren zuerst prüfen, sonst würde "N" auch "Shift+N" verbrauchen
+        bindings.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut.modifiers)));
+        Self { bindings }
+    }
+
+    fn to_config(&self) -> BTreeMap<Action, Vec<String>> {
+        let mut config = BTreeMap::new();
+        for action in Action::ALL {
+            config.insert(action, self.shortcuts(action).map(format_shortcut).collect());
+        }
+        config
+    }
+
+    pub fn shortcuts(&self, action: Action) -> impl Iterator<Item = &KeyboardShortcut> {
+        self.bindings
+            .iter()
+            .filter(move |(a, _)| *a == action)
+            .map(|(_, shortcut)| shortcut)
+    }
+
+    pub fn describe(&self, action: Action) -> String {
+        self.shortcuts(action).map(format_shortcut).collect::<Vec<_>>().join(", ")
+    }
+
+    // Während in ein Textfeld getippt wird, zählen nur Kürzel mit Strg/Alt oder Funktionstasten.
+    // Aus der Befehlssuche heraus bleibt die Liste per Pfeiltasten bedienbar; ein offenes
+    // Vorschlags-Popup hat die Tasten zu diesem Zeitpunkt schon verbraucht.
+    pub fn consume(&self, ctx: &egui::Context) -> Vec<Action> {
+        let typing = ctx.wants_keyboard_input();
+        let in_command_search = ctx.memory(|m| m.has_focus(egui::Id::new(crate::ui::COMMAND_SEARCH_ID)));
+        let mut actions = Vec::new();
+        ctx.input_mut(|i| {
+            for (action, shortcut) in &self.bindings {
+                let list_navigation = in_command_search && matches!(action, Action::SelectNext | Action::SelectPrevious);
+                if typing && !works_while_typing(shortcut) && !list_navigation {
+                    continue;
+                }
+                if i.consume_shortcut(shortcut) && !actions.contains(action) {
+                    actions.push(*action);
+                }
+            }
+        });
+        actions
+    }
+}
+
+fn modifier_count(modifiers: Modifiers) -> usize {
+    [modifiers.alt, modifiers.ctrl || modifiers.command, modifiers.shift]
+        .iter()
+        .filter(|m| **m)
+        .count()
+}
+
+fn works_while_typing(shortcut: &KeyboardShortcut) -> bool {
+    let modifiers = shortcut.modifiers;
+    let name = shortcut.logical_key.name();
+    let is_function_key = name.len() > 1 && name.starts_with('F') && name[1..].chars().all(|c| c.is_ascii_digit());
+    modifiers.alt || modifiers.ctrl || modifiers.command || is_function_key
+}
+
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    // "+" als Taste muss am Ende stehen, daher rsplit auf den letzten Trenner
+    let (modifier_part, key_part) = match text.rsplit_once('+') {
+        Some((mods, "")) => (mods.strip_suffix('+').unwrap_or(""), "+"),
+        Some((mods, key)) => (mods, key),
+        None => ("", text),
+    };
+    let mut modifiers = Modifiers::NONE;
+    for modifier in modifier_part.split('+').filter(|m| !m.is_empty()) {
+        match modifier.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" => modifiers = modifiers | Modifiers::COMMAND,
+            "alt" | "option" => modifiers = modifiers | Modifiers::ALT,
+            "shift" => modifiers = modifiers | Modifiers::SHIFT,
+            _ => return None,
+        }
+    }
+    Key::from_name(key_part).map(|key| KeyboardShortcut::new(modifiers, key))
+}
+
+pub fn format_shortcut(shortcut: &KeyboardShortcut) -> String {
+    let modifiers = shortcut.modifiers;
+    let mut parts = Vec::new();
+    if modifiers.ctrl || modifiers.command {
+        parts.push("Ctrl");
+    }
+    if modifiers.alt {
+        parts.push("Alt");
+    }
+    if modifiers.shift {
+        parts.push("Shift");
+    }
+    parts.push(shortcut.logical_key.name());
+    parts.join("+")
+}
+
+impl MyApp {
+    // Aus der Befehlssuche heraus gelten Pfeiltasten immer der Befehlsliste
+    fn navigation_pane(&self, ctx: &egui::Context) -> Pane {
+        if ctx.memory(|m| m.has_focus(egui::Id::new(crate::ui::COMMAND_SEARCH_ID))) {
+            Pane::CommandList
+        } else {
+            self.focused_pane
+        }
+    }
+
+    pub fn run_action(&mut self, action: Action, ctx: &egui::Context) {
+        match action {
+            Action::FocusSearch => {
+                self.focused_pane = Pane::CommandList;
+                ctx.memory_mut(|m| m.request_focus(egui::Id::new(crate::ui::COMMAND_SEARCH_ID)));
+            }
+            Action::FocusManpageSearch => {
+                self.focused_pane = Pane::Manpage;
+                ctx.memory_mut(|m| m.request_focus(egui::Id::new(crate::ui::MANPAGE_SEARCH_ID)));
+            }
+            Action::NextPane | Action::PreviousPane => {
+                ctx.memory_mut(|m| m.stop_text_input());
+                self.focused_pane = match self.focused_pane {
+                    Pane::CommandList => Pane::Manpage,
+                    Pane::Manpage => Pane::CommandList,
+                };
+            }
+            Action::SelectNext => match self.navigation_pane(ctx) {
+                Pane::CommandList => self.select_next(),
+                Pane::Manpage => self.scroll_manpage_by(self.manpage_line_height),
+            },
+            Action::SelectPrevious => match self.navigation_pane(ctx) {
+                Pane::CommandList => self.select_previous(),
+                Pane::Manpage => self.scroll_manpage_by(-self.manpage_line_height),
+            },
+            Action::PageUp => self.scroll_manpage_by(-self.manpage_viewport_height),
+            Action::PageDown => self.scroll_manpage_by(self.manpage_viewport_height),
+            Action::ScrollToTop => {
+                self.scroll_to_top = true;
+                self.scroll_to_bottom = false;
+            }
+            Action::ScrollToBottom => {
+                self.scroll_to_bottom = true;
+                self.scroll_to_top = false;
+            }
+            Action::NextMatch => self.jump_to_match(1),
+            Action::PreviousMatch => self.jump_to_match(-1),
+            Action::OpenExample => self.open_first_example(ctx),
+            Action::ToggleFavorite => {
+                if let Some(command) = self.selected_command.clone() {
+                    self.toggle_favorite(&command);
+                }
+            }
+            Action::Back => self.go_back(),
+            Action::Forward => self.go_forward(),
+            Action::ExplainCommand => self.explain_open = !self.explain_open,
+            Action::RunCommand => self.open_run_panel(None),
+            Action::Inventory => self.inventory_open = !self.inventory_open,
+            Action::PathCheck => self.toggle_path_check(),
+            Action::WhatsNew => self.toggle_whats_new(),
+            Action::NewTab => {
+                if let Some(command) = self.selected_command.clone() {
+                    self.open_in_new_tab(&command);
+                }
+            }
+            Action::CloseTab => self.close_tab(self.active_tab),
+            Action::NextTab => self.cycle_tab(1),
+            Action::PreviousTab => self.cycle_tab(-1),
+            Action::SplitView => self.toggle_split(),
+            Action::CommandPalette => {
+                self.command_palette_open = !self.command_palette_open;
+                self.command_palette_query.clear();
+                self.command_palette_index = 0;
+            }
+            Action::ToggleHelp => self.help_open = !self.help_open,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(key: Key, modifiers: Modifiers) -> egui::Event {
+        egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers }
+    }
+
+    // Ein Frame mit den gegebenen Tastenereignissen; liefert die ausgelösten Aktionen
+    fn consumed(keymap: &Keymap, events: Vec<egui::Event>) -> Vec<Action> {
+        let ctx = egui::Context::default();
+        let mut actions = Vec::new();
+        let _ = ctx.run(egui::RawInput { events, ..Default::default() }, |ctx| actions = keymap.consume(ctx));
+        actions
+    }
+
+    #[test]
+    fn shortcuts_with_modifiers_and_symbol_keys() {
+        let parsed = |text| parse_shortcut(text).map(|shortcut| (shortcut.modifiers, shortcut.logical_key));
+        assert_eq!(parsed("Ctrl+Shift+F"), Some((Modifiers::COMMAND | Modifiers::SHIFT, Key::F)));
+        assert_eq!(parsed("alt+Left"), Some((Modifiers::ALT, Key::ArrowLeft)));
+        assert_eq!(parsed("/"), Some((Modifiers::NONE, Key::Slash)));
+        assert_eq!(parsed("?"), Some((Modifiers::NONE, Key::Questionmark)));
+        assert_eq!(parsed("Ctrl+Backslash"), Some((Modifiers::COMMAND, Key::Backslash)));
+        assert_eq!(parsed("Ctrl++"), Some((Modifiers::COMMAND, Key::Plus)));
+        assert_eq!(parsed("Hyper+F"), None);
+        assert_eq!(parsed("Ctrl+Nope"), None);
+        assert_eq!(parsed(""), None);
+    }
+
+    #[test]
+    fn formatted_shortcuts_parse_back() {
+        let keymap = Keymap::default();
+        for action in Action::ALL {
+            for shortcut in keymap.shortcuts(action) {
+                assert_eq!(parse_shortcut(&format_shortcut(shortcut)).as_ref(), Some(shortcut));
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_bindings_are_ignored() {
+        let overrides = BTreeMap::from([(Action::FocusSearch, vec!["Hyper+F".to_string(), "F3".to_string()])]);
+        assert_eq!(Keymap::from_config(&overrides).describe(Action::FocusSearch), "F3");
+    }
+
+    #[test]
+    fn consume_prefers_the_shortcut_with_more_modifiers() {
+        let keymap = Keymap::default();
+        assert_eq!(consumed(&keymap, vec![press(Key::N, Modifiers::SHIFT)]), [Action::PreviousMatch]);
+        assert_eq!(consumed(&keymap, vec![press(Key::N, Modifiers::NONE)]), [Action::NextMatch]);
+        assert_eq!(consumed(&keymap, vec![press(Key::Slash, Modifiers::NONE)]), [Action::FocusSearch]);
+        assert_eq!(consumed(&keymap, vec![press(Key::Backslash, Modifiers::COMMAND)]), [Action::SplitView]);
+        // "?" wird auf den meisten Tastaturen mit Shift getippt
+        assert_eq!(consumed(&keymap, vec![press(Key::Questionmark, Modifiers::SHIFT)]), [Action::ToggleHelp]);
+        assert!(consumed(&keymap, vec![press(Key::Q, Modifiers::NONE)]).is_empty());
+    }
+}
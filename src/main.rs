@@ -1,6 +1,34 @@
 mod app;
+mod builder;
+mod categories;
 mod cli;
+mod command_filter;
+mod command_list;
+mod completion;
+mod config;
+mod documentation;
+mod examples;
+mod explain;
+mod favorites;
+mod highlight;
+mod inventory;
+mod keymap;
 mod manpage;
+mod options;
+mod package;
+mod path_check;
+mod path_watch;
+mod process;
+mod sandbox;
+mod search_history;
+mod session;
+mod shell;
+mod subcommands;
+mod tabs;
+mod ui;
+mod variants;
+mod version;
+mod whats_new;
 
 use eframe::NativeOptions;
 
@@ -10,7 +38,7 @@ fn main() {
     eframe::run_native(
         "CLI Organizer",
         options,
-        Box::new(|_cc| Ok(Box::new(app::MyApp::new()))),
+        Box::new(|cc| Ok(Box::new(app::MyApp::new(cc)))),
 
     ).unwrap();
 }
@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
 use std::process::Command;
 use log::debug;
 use std::io;
 
+const DEFAULT_MAN_DIRS: [&str; 3] = ["/usr/share/man", "/usr/local/share/man", "/usr/local/man"];
+
 pub fn get_manpage(command: &str) -> Result<String, io::Error> {
     debug!("Fetching manpage for command: {}", command);
     let output = Command::new("man")
@@ -12,6 +17,184 @@ pub fn get_manpage(command: &str) -> Result<String, io::Error> {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
         let error = String::from_utf8_lossy(&output.stderr);
-        Err(io::Error::new(io::ErrorKind::Other, error.to_string()))
+        Err(io::Error::other(error.to_string()))
+    }
+}
+
+pub fn filter_lines(manpage: &str, query: &str) -> String {
+    if query.is_empty() {
+        return manpage.to_string();
+    }
+
+    manpage
+        .lines()
+        .filter(|line| line.contains(query))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Index der nächsten bzw. vorherigen Zeile der gefilterten Manpage, die den Suchbegriff enthält; am Ende geht es vorn weiter
+pub fn next_match(manpage: &str, query: &str, current: Option<usize>, direction: isize) -> Option<usize> {
+    let match_count = filter_lines(manpage, query).lines().count();
+    if match_count == 0 {
+        return None;
+    }
+    Some(match current {
+        Some(index) => (index as isize + direction).rem_euclid(match_count as isize) as usize,
+        None if direction < 0 => match_count - 1,
+        None => 0,
+    })
+}
+
+// Überschriften stehen in der Ausgabe von man in Spalte 0 und enthalten keine Kleinbuchstaben.
+// Kopf- und Fußzeile ("LS(1)  User Commands  LS(1)") fallen damit heraus.
+pub fn is_section_heading(line: &str) -> bool {
+    let trimmed = line.trim_end();
+    !trimmed.is_empty()
+        && !line.starts_with(char::is_whitespace)
+        && trimmed.chars().any(|c| c.is_alphabetic())
+        && !trimmed.chars().any(|c| c.is_lowercase())
+}
+
+// Häufigste Einrückung des Fließtexts, bei man-db üblicherweise 7 Leerzeichen
+pub fn body_indent(manpage: &str) -> usize {
+    let mut counts = [0usize; 32];
+    for line in manpage.lines() {
+        let indent = line.len() - line.trim_start().len();
+        if !line.trim().is_empty() && indent > 0 && indent < counts.len() {
+            counts[indent] += 1;
+        }
+    }
+    counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, count)| **count)
+        .map(|(indent, _)| indent)
+        .filter(|indent| *indent > 0)
+        .unwrap_or(7)
+}
+
+pub fn is_examples_section(title: &str) -> bool {
+    matches!(title, "EXAMPLES" | "EXAMPLE")
+}
+
+// Liest Name und Abschnitt aus der Kopfzeile, z. B. "SSH_CONFIG(5)" -> ("ssh_config", "5")
+pub fn page_name_and_section(manpage: &str) -> Option<(String, String)> {
+    let header = manpage.lines().find(|line| !line.trim().is_empty())?;
+    let first = header.split_whitespace().next()?;
+    let (name, rest) = first.split_once('(')?;
+    let section = rest.strip_suffix(')')?;
+    Some((name.to_lowercase(), section.to_string()))
+}
+
+// Ein Eintrag in einer Seite, die mehrere Befehle beschreibt (zshbuiltins(1), COMMANDS in systemctl(1)):
+// Er beginnt mit einer oder mehreren Zeilen, die mit `name` anfangen, gefolgt von stärker eingerücktem Text.
+pub fn extract_entry(manpage: &str, name: &str) -> Option<String> {
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let starts_with_name = |line: &str| line.split_whitespace().next() == Some(name);
+
+    let lines: Vec<&str> = manpage.lines().collect();
+    let start = lines.iter().enumerate().position(|(index, line)| {
+        starts_with_name(line)
+            && lines[index + 1..]
+                .iter()
+                .find(|next| !next.trim().is_empty() && !starts_with_name(next))
+                .is_some_and(|next| indent_of(next) > indent_of(line))
+    })?;
+    let indent = indent_of(lines[start]);
+    let mut end = start + 1;
+    while end < lines.len() {
+        let line = lines[end];
+        let belongs = line.trim().is_empty()
+            || indent_of(line) > indent
+            || (indent_of(line) == indent && starts_with_name(line));
+        if !belongs {
+            break;
+        }
+        end += 1;
+    }
+    Some(lines[start..end].join("\n").trim_end().to_string())
+}
+
+pub fn man_dirs() -> Vec<PathBuf> {
+    let manpath = Command::new("manpath")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|manpath| !manpath.is_empty())
+        .or_else(|| std::env::var("MANPATH").ok());
+    match manpath {
+        Some(manpath) => manpath.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from).collect(),
+        None => DEFAULT_MAN_DIRS.iter().map(PathBuf::from).collect(),
+    }
+}
+
+pub fn strip_compression(file_name: &str) -> &str {
+    [".gz", ".bz2", ".xz", ".zst", ".lzma"]
+        .iter()
+        .find_map(|suffix| file_name.strip_suffix(suffix))
+        .unwrap_or(file_name)
+}
+
+// Name -> Abschnitt aller installierten Manpages, z. B. "ls" -> "1" für man1/ls.1.gz.
+// Gibt es eine Seite in mehreren Abschnitten (printf), zählen Befehlsabschnitte (1, 8, 6) vor den übrigen.
+// Deutlich schneller als `man` pro Befehl.
+pub fn installed_pages() -> HashMap<String, String> {
+    let mut pages = HashMap::new();
+    for dir in man_dirs() {
+        let Ok(sections) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for section in sections.flatten() {
+            let section_name = section.file_name().to_string_lossy().to_string();
+            let Some(section_number) = section_name.strip_prefix("man") else {
+                continue;
+            };
+            let Ok(files) = fs::read_dir(section.path()) else {
+                continue;
+            };
+            for page in files.flatten() {
+                let file_name = page.file_name().to_string_lossy().to_string();
+                let Some((name, _)) = strip_compression(&file_name).rsplit_once('.') else {
+                    continue;
+                };
+                let existing = pages.entry(name.to_string()).or_insert_with(|| section_number.to_string());
+                if section_rank(section_number) < section_rank(existing) {
+                    *existing = section_number.to_string();
+                }
+            }
+        }
+    }
+    pages
+}
+
+fn section_rank(section: &str) -> (usize, &str) {
+    let rank = match section.chars().next() {
+        Some('1') => 0,
+        Some('8') => 1,
+        Some('6') => 2,
+        _ => 3,
+    };
+    (rank, section)
+}
+
+// Kurzbeschreibungen aus der man-Datenbank: "ls (1) - list directory contents" -> "ls" -> "list directory contents".
+// Ohne man-db (apropos) leer.
+pub fn page_descriptions() -> HashMap<String, String> {
+    let mut descriptions = HashMap::new();
+    let Ok(output) = Command::new("apropos").arg(".").output() else {
+        return descriptions;
+    };
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((names, description)) = line.split_once(" - ") else {
+            continue;
+        };
+        let Some((names, _section)) = names.trim().rsplit_once(" (") else {
+            continue;
+        };
+        for name in names.split(", ") {
+            descriptions.entry(name.trim().to_string()).or_insert_with(|| description.trim().to_string());
+        }
     }
+    descriptions
 }
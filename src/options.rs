@@ -0,0 +1,192 @@
+use crate::manpage::{body_indent, is_section_heading};
+
+#[derive(Clone)]
+pub struct ManOption {
+    pub flags: Vec<String>,
+    pub argument: Option<String>,
+    pub optional_argument: bool,
+    pub description: String,
+}
+
+impl ManOption {
+    pub fn matches(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn display_flags(&self) -> String {
+        let mut text = self.flags.join(", ");
+        if let Some(argument) = &self.argument {
+            if self.optional_argument {
+                text.push_str(&format!("[={}]", argument));
+            } else {
+                text.push(' ');
+                text.push_str(argument);
+            }
+        }
+        text
+    }
+}
+
+// Optionen stehen in man-db-Ausgaben als Marke auf Höhe des Fließtexts ("-a, --all"),
+// die Beschreibung folgt tiefer eingerückt oder nach mindestens zwei Leerzeichen in derselben Zeile.
+pub fn parse_options(manpage: &str) -> Vec<ManOption> {
+    let body_indent = body_indent(manpage);
+    let lines: Vec<&str> = manpage.lines().collect();
+    let mut options = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        let indent = indent_of(line);
+        let trimmed = line.trim();
+        if !trimmed.starts_with('-') || trimmed == "-" || trimmed == "--" || indent > body_indent + 3 || is_section_heading(line) {
+            continue;
+        }
+
+        let (tag, inline_description) = match trimmed.find("  ") {
+            Some(split) => (&trimmed[..split], trimmed[split..].trim()),
+            None => (trimmed, ""),
+        };
+        let Some((flags, argument, optional_argument)) = parse_tag(tag) else {
+            continue;
+        };
+
+        let mut description: Vec<&str> = Vec::new();
+        if !inline_description.is_empty() {
+            description.push(inline_description);
+        }
+        // Erster Absatz der Beschreibung: tiefer eingerückte Zeilen bis zur nächsten Leerzeile
+        while index < lines.len() {
+            let next = lines[index];
+            if next.trim().is_empty() {
+                if description.is_empty() {
+                    index += 1;
+                    continue;
+                }
+                break;
+            }
+            if indent_of(next) <= indent {
+                break;
+            }
+            description.push(next.trim());
+            index += 1;
+        }
+
+        options.push(ManOption {
+            flags,
+            argument,
+            optional_argument,
+            description: description.join(" "),
+        });
+    }
+    options
+}
+
+// "-w COLS, --width=COLS" -> (["-w", "--width"], Some("COLS"), false)
+fn parse_tag(tag: &str) -> Option<(Vec<String>, Option<String>, bool)> {
+    let mut flags = Vec::new();
+    let mut argument = None;
+    let mut optional_argument = false;
+    for spec in tag.split(", ").map(str::trim) {
+        if !spec.starts_with('-') {
+            return None;
+        }
+        let end = spec.find(['=', '[', ' ']).unwrap_or(spec.len());
+        let flag = &spec[..end];
+        if flag.len() < 2 || !flag.trim_start_matches('-').chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '?') {
+            return None;
+        }
+        flags.push(flag.to_string());
+
+        let rest = spec[end..].trim();
+        if !rest.is_empty() {
+            optional_argument = rest.starts_with('[');
+            let name = rest.trim_matches(|c: char| matches!(c, '[' | ']' | '=' | ' '));
+            if !name.is_empty() {
+                argument = Some(name.to_string());
+            }
+        }
+    }
+    (!flags.is_empty()).then_some((flags, argument, optional_argument))
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+// Großgeschriebene Operanden aus der SYNOPSIS, z. B. "cp [OPTION]... SOURCE DEST" -> ["SOURCE", "DEST"]
+pub fn parse_positionals(manpage: &str) -> Vec<String> {
+    let mut positionals: Vec<String> = Vec::new();
+    let mut in_synopsis = false;
+    for line in manpage.lines() {
+        if is_section_heading(line) {
+            in_synopsis = line.trim() == "SYNOPSIS";
+            continue;
+        }
+        if !in_synopsis {
+            continue;
+        }
+        for token in line.split_whitespace().skip(1) {
+            let name = token.trim_matches(|c: char| matches!(c, '[' | ']' | '.' | '|' | '<' | '>'));
+            let is_operand = !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_uppercase() || c == '_')
+                && name != "OPTION"
+                && name != "OPTIONS";
+            if is_operand && !positionals.iter().any(|p| p == name) {
+                positionals.push(name.to_string());
+            }
+        }
+    }
+    positionals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANPAGE: &str = concat!(
+        "LS(1)     User Commands     LS(1)\n",
+        "\n",
+        "SYNOPSIS\n",
+        "       ls [OPTION]... [FILE]...\n",
+        "\n",
+        "DESCRIPTION\n",
+        "       List information about the FILEs.\n",
+        "\n",
+        "       -a, --all\n",
+        "              do not ignore entries starting with .\n",
+        "\n",
+        "       --color[=WHEN]\n",
+        "              colorize the output; WHEN can be 'always',\n",
+        "              'auto', or 'never'\n",
+        "\n",
+        "              more details in a second paragraph\n",
+        "\n",
+        "       -w COLS, --width=COLS\n",
+        "              set output width to COLS\n",
+        "\n",
+        "       -1     list one file per line\n",
+        "\n",
+        "       - this is not an option\n",
+    );
+
+    #[test]
+    fn options_with_arguments_and_descriptions() {
+        let options = parse_options(MANPAGE);
+        let flags: Vec<String> = options.iter().map(ManOption::display_flags).collect();
+        assert_eq!(flags, ["-a, --all", "--color[=WHEN]", "-w, --width COLS", "-1"]);
+        assert_eq!(options[0].description, "do not ignore entries starting with .");
+        assert!(options[1].optional_argument);
+        assert_eq!(options[1].description, "colorize the output; WHEN can be 'always', 'auto', or 'never'");
+        assert!(!options[2].optional_argument);
+        assert_eq!(options[2].argument.as_deref(), Some("COLS"));
+        assert_eq!(options[3].description, "list one file per line");
+        assert!(options[0].matches("--all") && !options[0].matches("-l"));
+    }
+
+    #[test]
+    fn positionals_from_the_synopsis() {
+        assert_eq!(parse_positionals(MANPAGE), ["FILE"]);
+    }
+}
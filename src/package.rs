@@ -0,0 +1,471 @@
+use crate::cli::command_path;
+use crate::process::output_with_timeout;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+const DPKG_DIR: &str = "/var/lib/dpkg";
+const PACMAN_DB_DIR: &str = "/var/lib/pacman/local";
+// rpm wartet bei gesperrter Datenbank, und die Abfrage läuft auch für ganze Kataloge einmal pro Befehl
+const RPM_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PackageManager {
+    Dpkg,
+    Rpm,
+    Pacman,
+    Nix,
+    Homebrew,
+    Cargo,
+    Pip,
+    Npm,
+}
+
+impl PackageManager {
+    pub fn label(self) -> &'static str {
+        match self {
+            PackageManager::Dpkg => "dpkg",
+            PackageManager::Rpm => "rpm",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Nix => "Nix",
+            PackageManager::Homebrew => "Homebrew",
+            PackageManager::Cargo => "cargo install",
+            PackageManager::Pip => "pip",
+            PackageManager::Npm => "npm -g",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PackageInfo {
+    pub manager: PackageManager,
+    pub name: String,
+    pub version: Option<String>,
+    // Sekunden seit 1970
+    pub installed_at: Option<u64>,
+    pub summary: Option<String>,
+}
+
+impl PackageInfo {
+    fn new(manager: PackageManager, name: &str, version: Option<String>, installed_at: Option<u64>) -> Self {
+        Self { manager, name: name.to_string(), version, installed_at, summary: None }
+    }
+}
+
+// Fragt Paketinformationen in einem eigenen Thread ab. Die Indizes von dpkg und pacman
+// werden dort beim ersten Bedarf einmal aufgebaut und danach wiederverwendet.
+pub struct PackageLookup {
+    requests: Sender<String>,
+    pub results: Receiver<(String, Option<PackageInfo>)>,
+}
+
+impl Default for PackageLookup {
+    fn default() -> Self {
+        let (requests, request_receiver) = mpsc::channel::<String>();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            let mut index = PackageIndex::default();
+            for command in request_receiver {
+                let info = command_path(&command).and_then(|path| index.lookup(&path));
+                if result_sender.send((command, info)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { requests, results }
+    }
+}
+
+impl PackageLookup {
+    pub fn request(&self, command: &str) {
+        let _ = self.requests.send(command.to_string());
+    }
+}
+
+#[derive(Default)]
+pub struct PackageIndex {
+    // Dateipfad -> Paketname
+    dpkg_files: Option<HashMap<String, String>>,
+    dpkg_packages: HashMap<String, DpkgPackage>,
+    pacman_files: Option<HashMap<String, PathBuf>>,
+}
+
+#[derive(Default)]
+struct DpkgPackage {
+    version: Option<String>,
+    summary: Option<String>,
+    // z. B. "net", "utils", "devel"; bei Paketen aus Fremdquellen mit Präfix wie "contrib/net"
+    section: Option<String>,
+}
+
+impl PackageIndex {
+    // Zuerst die Paketmanager, die man am Pfad erkennt, danach die Systemdatenbanken. pip kommt zuletzt,
+    // sonst gälten Python-Werkzeuge der Distribution in /usr/bin (meson) als pip-Pakete.
+    pub fn lookup(&mut self, path: &Path) -> Option<PackageInfo> {
+        let resolved = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let info = nix_package(&resolved)
+            .or_else(|| homebrew_package(&resolved))
+            .or_else(|| npm_package(&resolved))
+            .or_else(|| cargo_package(path))
+            .or_else(|| self.dpkg_package(path, &resolved))
+            .or_else(|| self.pacman_package(path, &resolved))
+            .or_else(|| rpm_package(path))
+            .or_else(|| pip_package(path));
+        debug!("Package for {}: {:?}", path.display(), info.as_ref().map(|info| &info.name));
+        info
+    }
+
+    fn dpkg_package(&mut self, path: &Path, resolved: &Path) -> Option<PackageInfo> {
+        if !Path::new(DPKG_DIR).exists() {
+            return None;
+        }
+        if self.dpkg_files.is_none() {
+            self.dpkg_files = Some(dpkg_file_index());
+            self.dpkg_packages = dpkg_status();
+        }
+        let files = self.dpkg_files.as_ref()?;
+        let package = path_variants(path, resolved).iter().find_map(|candidate| files.get(candidate))?;
+        // Dateinamen in info/ tragen bei Multi-Arch-Paketen die Architektur, z. B. "libc6:amd64.list"
+        let name = package.split(':').next().unwrap_or(package);
+        let details = self.dpkg_packages.get(name);
+        let installed_at = modified_secs(&Path::new(DPKG_DIR).join("info").join(format!("{}.list", package)));
+        let mut info = PackageInfo::new(PackageManager::Dpkg, name, details.and_then(|d| d.version.clone()), installed_at);
+        info.summary = details.and_then(|d| d.summary.clone());
+        Some(info)
+    }
+
+    fn pacman_package(&mut self, path: &Path, resolved: &Path) -> Option<PackageInfo> {
+        if !Path::new(PACMAN_DB_DIR).exists() {
+            return None;
+        }
+        let files = self.pacman_files.get_or_insert_with(pacman_file_index);
+        let package_dir = path_variants(path, resolved).iter().find_map(|candidate| files.get(candidate))?;
+        let desc = fs::read_to_string(package_dir.join("desc")).ok()?;
+        let fields = pacman_fields(&desc);
+        let mut info = PackageInfo::new(
+            PackageManager::Pacman,
+            fields.get("NAME")?,
+            fields.get("VERSION").cloned(),
+            fields.get("INSTALLDATE").and_then(|date| date.parse().ok()),
+        );
+        info.summary = fields.get("DESC").cloned();
+        Some(info)
+    }
+}
+
+// Durch usrmerge stehen Dateien in den Datenbanken oft als /bin/ls, obwohl PATH /usr/bin/ls liefert
+fn path_variants(path: &Path, resolved: &Path) -> Vec<String> {
+    let mut variants = Vec::new();
+    for candidate in [path, resolved] {
+        let text = candidate.to_string_lossy().to_string();
+        if let Some(stripped) = text.strip_prefix("/usr") {
+            variants.push(stripped.to_string());
+        } else {
+            variants.push(format!("/usr{}", text));
+        }
+        variants.push(text);
+    }
+    variants
+}
+
+// Befehlsname -> (Sektion, Kurzbeschreibung) des dpkg-Pakets, zu dem er gehört. Leer ohne dpkg.
+pub fn dpkg_command_sections() -> HashMap<String, (String, Option<String>)> {
+    let mut sections = HashMap::new();
+    if !Path::new(DPKG_DIR).exists() {
+        return sections;
+    }
+    let packages = dpkg_status();
+    for (path, package) in dpkg_file_index() {
+        let name = package.split(':').next().unwrap_or(&package);
+        let Some(details) = packages.get(name) else {
+            continue;
+        };
+        let (Some(command), Some(section)) = (Path::new(&path).file_name(), &details.section) else {
+            continue;
+        };
+        sections
+            .entry(command.to_string_lossy().to_string())
+            .or_insert_with(|| (section.clone(), details.summary.clone()));
+    }
+    sections
+}
+
+fn dpkg_file_index() -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    let Ok(entries) = fs::read_dir(Path::new(DPKG_DIR).join("info")) else {
+        return index;
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(package) = file_name.strip_suffix(".list") else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        // Nur ausführbare Orte interessieren, das hält den Index klein
+        for line in content.lines().filter(|line| line.contains("bin/")) {
+            index.insert(line.to_string(), package.to_string());
+        }
+    }
+    debug!("Indexed {} executables from dpkg", index.len());
+    index
+}
+
+fn dpkg_status() -> HashMap<String, DpkgPackage> {
+    let mut packages = HashMap::new();
+    let Ok(status) = fs::read_to_string(Path::new(DPKG_DIR).join("status")) else {
+        return packages;
+    };
+    for paragraph in status.split("\n\n") {
+        let mut name = None;
+        let mut package = DpkgPackage::default();
+        for line in paragraph.lines() {
+            if let Some(value) = line.strip_prefix("Package: ") {
+                name = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Version: ") {
+                package.version = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Description: ") {
+                package.summary = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Section: ") {
+                package.section = Some(value.rsplit('/').next().unwrap_or(value).to_string());
+            }
+        }
+        if let Some(name) = name {
+            packages.insert(name, package);
+        }
+    }
+    packages
+}
+
+fn pacman_file_index() -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    let Ok(entries) = fs::read_dir(PACMAN_DB_DIR) else {
+        return index;
+    };
+    for entry in entries.flatten() {
+        let Ok(content) = fs::read_to_string(entry.path().join("files")) else {
+            continue;
+        };
+        // Pfade stehen ohne führenden Schrägstrich unter %FILES%
+        for line in content.lines().filter(|line| line.contains("bin/") && !line.ends_with('/')) {
+            index.insert(format!("/{}", line), entry.path());
+        }
+    }
+    index
+}
+
+// "%NAME%\nripgrep\n\n%VERSION%\n14.1.0-1" -> {"NAME": "ripgrep", "VERSION": "14.1.0-1"}
+fn pacman_fields(desc: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut lines = desc.lines();
+    while let Some(line) = lines.next() {
+        if let Some(key) = line.strip_prefix('%').and_then(|line| line.strip_suffix('%')) {
+            if let Some(value) = lines.next() {
+                fields.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    fields
+}
+
+fn rpm_package(path: &Path) -> Option<PackageInfo> {
+    let mut command = Command::new("rpm");
+    command
+        .args(["-qf", "--queryformat", "%{NAME}\\t%{VERSION}-%{RELEASE}\\t%{INSTALLTIME}\\t%{SUMMARY}\\n"])
+        .arg(path);
+    let output = output_with_timeout(&mut command, RPM_TIMEOUT).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut fields = text.lines().next()?.split('\t');
+    let mut info = PackageInfo::new(
+        PackageManager::Rpm,
+        fields.next()?,
+        fields.next().map(str::to_string),
+        fields.next().and_then(|time| time.parse().ok()),
+    );
+    info.summary = fields.next().map(str::to_string);
+    Some(info)
+}
+
+// /nix/store/<hash>-ripgrep-14.1.0/bin/rg
+fn nix_package(resolved: &Path) -> Option<PackageInfo> {
+    let rest = resolved.to_str()?.strip_prefix("/nix/store/")?;
+    let store_name = rest.split('/').next()?;
+    let (_, name_version) = store_name.split_once('-')?;
+    let (name, version) = split_name_version(name_version);
+    // Der Store setzt alle Zeitstempel auf 1970, ein Installationsdatum gibt es daher nicht
+    Some(PackageInfo::new(PackageManager::Nix, name, version, None))
+}
+
+// "ripgrep-14.1.0" -> ("ripgrep", Some("14.1.0")); die Version beginnt nach dem letzten "-" vor einer Ziffer
+fn split_name_version(name_version: &str) -> (&str, Option<String>) {
+    let split = name_version
+        .match_indices('-')
+        .map(|(index, _)| index)
+        .find(|index| name_version[index + 1..].starts_with(|c: char| c.is_ascii_digit()));
+    match split {
+        Some(index) => (&name_version[..index], Some(name_version[index + 1..].to_string())),
+        None => (name_version, None),
+    }
+}
+
+// .../Cellar/<name>/<version>/bin/<befehl>
+fn homebrew_package(resolved: &Path) -> Option<PackageInfo> {
+    let components: Vec<String> = resolved.iter().map(|part| part.to_string_lossy().to_string()).collect();
+    let cellar = components.iter().position(|part| part == "Cellar")?;
+    let name = components.get(cellar + 1)?;
+    let version = components.get(cellar + 2)?;
+    let keg: PathBuf = components[..cellar + 3].iter().collect();
+    let installed_at = modified_secs(&keg.join("INSTALL_RECEIPT.json")).or_else(|| modified_secs(&keg));
+    Some(PackageInfo::new(PackageManager::Homebrew, name, Some(version.clone()), installed_at))
+}
+
+// Globale npm-Befehle sind Symlinks nach .../lib/node_modules/<paket>/...
+fn npm_package(resolved: &Path) -> Option<PackageInfo> {
+    let components: Vec<String> = resolved.iter().map(|part| part.to_string_lossy().to_string()).collect();
+    let modules = components.iter().position(|part| part == "node_modules")?;
+    let mut end = modules + 2;
+    // Pakete mit Scope wie @angular/cli
+    if components.get(modules + 1)?.starts_with('@') {
+        end += 1;
+    }
+    let package_dir: PathBuf = components.get(..end)?.iter().collect();
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(package_dir.join("package.json")).ok()?).ok()?;
+    let mut info = PackageInfo::new(
+        PackageManager::Npm,
+        manifest["name"].as_str()?,
+        manifest["version"].as_str().map(str::to_string),
+        modified_secs(&package_dir),
+    );
+    info.summary = manifest["description"].as_str().map(str::to_string);
+    Some(info)
+}
+
+// ~/.cargo/.crates2.json: {"installs": {"ripgrep 14.1.0 (registry+...)": {"bins": ["rg"], ...}}}
+fn cargo_package(path: &Path) -> Option<PackageInfo> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))?;
+    if path.parent()? != cargo_home.join("bin") {
+        return None;
+    }
+    let command = path.file_name()?.to_str()?;
+    let crates: serde_json::Value = serde_json::from_str(&fs::read_to_string(cargo_home.join(".crates2.json")).ok()?).ok()?;
+    let (key, _) = crates["installs"].as_object()?.iter().find(|(_, install)| {
+        install["bins"].as_array().is_some_and(|bins| bins.iter().any(|bin| bin.as_str() == Some(command)))
+    })?;
+    let mut parts = key.split_whitespace();
+    Some(PackageInfo::new(
+        PackageManager::Cargo,
+        parts.next()?,
+        parts.next().map(str::to_string),
+        modified_secs(path),
+    ))
+}
+
+// pip install --user legt Befehle in ~/.local/bin ab, pip in einer venv in <venv>/bin.
+// Zu welchem Paket sie gehören, steht in entry_points.txt.
+fn pip_package(path: &Path) -> Option<PackageInfo> {
+    let bin_dir = path.parent()?;
+    let prefix = bin_dir.parent()?;
+    if bin_dir.file_name()? != "bin" {
+        return None;
+    }
+    let user_prefix = dirs::home_dir().is_some_and(|home| prefix == home.join(".local"));
+    if !user_prefix && !prefix.join("pyvenv.cfg").is_file() {
+        return None;
+    }
+    let command = path.file_name()?.to_str()?;
+    for lib in fs::read_dir(prefix.join("lib")).ok()?.flatten() {
+        let site_packages = lib.path().join("site-packages");
+        let Ok(entries) = fs::read_dir(&site_packages) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir_name = entry.file_name().to_string_lossy().to_string();
+            let Some(name_version) = dir_name.strip_suffix(".dist-info") else {
+                continue;
+            };
+            let Ok(entry_points) = fs::read_to_string(entry.path().join("entry_points.txt")) else {
+                continue;
+            };
+            let provides = entry_points
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .any(|(name, _)| name.trim() == command);
+            if provides {
+                let (name, version) = split_name_version(name_version);
+                return Some(PackageInfo::new(PackageManager::Pip, name, version, modified_secs(&entry.path())));
+            }
+        }
+    }
+    None
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|duration| duration.as_secs())
+}
+
+// Sekunden seit 1970 als "2024-05-17" (UTC), ohne zusätzliche Abhängigkeit
+pub fn format_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    // Umrechnung nach Howard Hinnant, "days_from_civil" rückwärts
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_and_version_split_at_the_first_numeric_part() {
+        assert_eq!(split_name_version("ripgrep-14.1.0"), ("ripgrep", Some("14.1.0".to_string())));
+        assert_eq!(split_name_version("python3-3.11.9"), ("python3", Some("3.11.9".to_string())));
+        assert_eq!(split_name_version("git-lfs-3.4.1"), ("git-lfs", Some("3.4.1".to_string())));
+        assert_eq!(split_name_version("hello"), ("hello", None));
+    }
+
+    #[test]
+    fn pacman_desc_fields() {
+        let fields = pacman_fields("%NAME%\nripgrep\n\n%VERSION%\n14.1.0-1\n\n%DESC%\nA search tool\n\n%INSTALLDATE%\n1715904000\n");
+        assert_eq!(fields["NAME"], "ripgrep");
+        assert_eq!(fields["VERSION"], "14.1.0-1");
+        assert_eq!(fields["DESC"], "A search tool");
+        assert_eq!(fields["INSTALLDATE"], "1715904000");
+    }
+
+    #[test]
+    fn usrmerge_path_variants() {
+        let variants = path_variants(Path::new("/bin/ls"), Path::new("/usr/bin/ls"));
+        assert_eq!(variants, ["/usr/bin/ls", "/bin/ls", "/bin/ls", "/usr/bin/ls"]);
+    }
+
+    #[test]
+    fn dates_in_utc() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_709_251_199), "2024-02-29");
+        assert_eq!(format_date(1_715_904_000), "2024-05-17");
+        assert_eq!(format_date(1_735_689_599), "2024-12-31");
+        assert_eq!(format_date(4_107_542_400), "2100-03-01");
+    }
+}
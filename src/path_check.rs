@@ -0,0 +1,119 @@
+use log::debug;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+pub struct PathDir {
+    // Wie in PATH angegeben, kann auch leer oder relativ sein
+    pub entry: String,
+    pub exists: bool,
+    pub world_writable: bool,
+    // Leere und relative Einträge hängen vom aktuellen Verzeichnis ab
+    pub relative: bool,
+    // Steht bereits weiter vorne in PATH
+    pub duplicate: bool,
+    pub command_count: usize,
+}
+
+pub struct BrokenEntry {
+    pub path: PathBuf,
+    // Ziel des Symlinks, bei nicht ausführbaren Dateien None
+    pub target: Option<PathBuf>,
+}
+
+#[derive(Default)]
+pub struct PathReport {
+    pub dirs: Vec<PathDir>,
+    // Befehl -> alle Fundstellen in PATH-Reihenfolge; die erste gewinnt
+    pub shadowed: BTreeMap<String, Vec<PathBuf>>,
+    pub broken_symlinks: Vec<BrokenEntry>,
+    pub not_executable: Vec<BrokenEntry>,
+}
+
+impl PathReport {
+    pub fn problem_count(&self) -> usize {
+        self.dirs
+            .iter()
+            .filter(|dir| !dir.exists || dir.world_writable || dir.relative || dir.duplicate)
+            .count()
+            + self.broken_symlinks.len()
+            + self.not_executable.len()
+    }
+}
+
+pub fn analyze() -> PathReport {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let mut report = PathReport::default();
+    let mut seen_dirs = HashSet::new();
+    let mut found: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for dir in std::env::split_paths(&path) {
+        let entry = dir.to_string_lossy().to_string();
+        let duplicate = !seen_dirs.insert(fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone()));
+        let metadata = fs::metadata(&dir).ok().filter(|metadata| metadata.is_dir());
+        let mut path_dir = PathDir {
+            relative: !dir.is_absolute(),
+            exists: metadata.is_some(),
+            world_writable: metadata.is_some_and(|metadata| metadata.permissions().mode() & 0o002 != 0),
+            duplicate,
+            command_count: 0,
+            entry,
+        };
+        // Ein doppelter Eintrag liefert dieselben Dateien noch einmal, das wäre keine echte Verdeckung
+        if !duplicate {
+            if let Ok(files) = fs::read_dir(&dir) {
+                for file in files.flatten() {
+                    let file_path = file.path();
+                    match inspect(&file_path) {
+                        FileState::Executable => {
+                            path_dir.command_count += 1;
+                            found.entry(file.file_name().to_string_lossy().to_string()).or_default().push(file_path);
+                        }
+                        FileState::BrokenSymlink(target) => {
+                            report.broken_symlinks.push(BrokenEntry { path: file_path, target: Some(target) });
+                        }
+                        FileState::NotExecutable => report.not_executable.push(BrokenEntry { path: file_path, target: None }),
+                        FileState::Other => {}
+                    }
+                }
+            }
+        }
+        report.dirs.push(path_dir);
+    }
+    report.shadowed = found.into_iter().filter(|(_, paths)| paths.len() > 1).collect();
+    debug!(
+        "Analyzed {} PATH entries: {} shadowed commands, {} problems",
+        report.dirs.len(),
+        report.shadowed.len(),
+        report.problem_count()
+    );
+    report
+}
+
+enum FileState {
+    Executable,
+    BrokenSymlink(PathBuf),
+    NotExecutable,
+    // Unterverzeichnisse u. Ä.
+    Other,
+}
+
+fn inspect(path: &Path) -> FileState {
+    let Ok(link_metadata) = fs::symlink_metadata(path) else {
+        return FileState::Other;
+    };
+    // metadata folgt dem Symlink und schlägt fehl, wenn das Ziel fehlt
+    let Ok(metadata) = fs::metadata(path) else {
+        if link_metadata.file_type().is_symlink() {
+            return FileState::BrokenSymlink(fs::read_link(path).unwrap_or_default());
+        }
+        return FileState::Other;
+    };
+    if !metadata.is_file() {
+        FileState::Other
+    } else if metadata.permissions().mode() & 0o111 != 0 {
+        FileState::Executable
+    } else {
+        FileState::NotExecutable
+    }
+}
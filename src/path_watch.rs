@@ -0,0 +1,54 @@
+use eframe::egui;
+use log::{debug, error};
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::sync::mpsc::{self, Receiver};
+
+// Beobachtet alle PATH-Verzeichnisse (unter Linux per inotify) und meldet, wenn dort Dateien
+// hinzukommen, verschwinden oder umbenannt werden. Was sich genau geändert hat, ermittelt die App selbst.
+pub struct PathWatcher {
+    // Muss am Leben bleiben, sonst endet die Beobachtung
+    _watcher: RecommendedWatcher,
+    pub changes: Receiver<()>,
+}
+
+impl PathWatcher {
+    pub fn start(ctx: egui::Context) -> Option<Self> {
+        let (sender, changes) = mpsc::channel();
+        let handler = move |result: notify::Result<notify::Event>| match result {
+            Ok(event) if is_relevant(&event.kind) => {
+                if sender.send(()).is_ok() {
+                    // Die Oberfläche schläft sonst bis zur nächsten Eingabe
+                    ctx.request_repaint();
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error!("PATH watcher error: {}", e),
+        };
+        let mut watcher = match notify::recommended_watcher(handler) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                error!("Failed to start PATH watcher: {}", e);
+                return None;
+            }
+        };
+        let path = std::env::var_os("PATH")?;
+        let mut watched = 0;
+        for dir in std::env::split_paths(&path).filter(|dir| dir.is_dir()) {
+            match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                Ok(()) => watched += 1,
+                Err(e) => debug!("Not watching {}: {}", dir.display(), e),
+            }
+        }
+        debug!("Watching {} PATH directories", watched);
+        Some(Self { _watcher: watcher, changes })
+    }
+}
+
+// Reine Inhaltsänderungen (z. B. beim Schreiben einer Datei) ändern die Befehlsliste nicht
+fn is_relevant(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_)) | EventKind::Modify(ModifyKind::Metadata(_))
+    )
+}
@@ -0,0 +1,158 @@
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Zeit zwischen SIGTERM und SIGKILL
+const KILL_GRACE: Duration = Duration::from_millis(300);
+// So lange dürfen die Lesethreads nach dem Ende des Programms noch Restausgabe einsammeln
+const READ_GRACE: Duration = Duration::from_millis(200);
+const TEMP_DIR_ATTEMPTS: usize = 16;
+
+// Startet das Programm als Anführer einer eigenen Prozessgruppe, damit sich Pipelines und
+// Hintergrundjobs (`sleep 100 | cat`, `foo & wait`) zusammen mit ihm beenden lassen
+pub fn spawn_in_group(command: &mut Command) -> io::Result<Child> {
+    command.process_group(0).spawn()
+}
+
+// Arbeitsverzeichnis (und HOME) für ein fremdes Programm im gemeinsamen /tmp: zufälliger Name, nur für uns
+// zugänglich und exklusiv angelegt. Ein vorhandener Pfad, etwa ein Symlink eines anderen Benutzers, wird nie übernommen.
+pub fn private_temp_dir(prefix: &str) -> io::Result<PathBuf> {
+    for _ in 0..TEMP_DIR_ATTEMPTS {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0));
+        let dir = std::env::temp_dir().join(format!("{}_{}_{:016x}", prefix, std::process::id(), hasher.finish()));
+        match fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("no free directory name for {}", prefix)))
+}
+
+fn signal_group(child: &Child, signal: libc::c_int) {
+    // Die Gruppen-ID ist die PID des Anführers; negativ adressiert kill(2) die ganze Gruppe
+    let pgid = child.id() as libc::pid_t;
+    unsafe {
+        libc::kill(-pgid, signal);
+    }
+}
+
+// Ob der Anführer beendet ist, ohne ihn abzuholen: Solange er als Zombie existiert, bleibt seine PID
+// und damit die Gruppen-ID belegt, sodass ein späteres Signal an die Gruppe keinen fremden Prozess trifft
+pub fn leader_exited(child: &Child) -> io::Result<bool> {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let flags = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+    if unsafe { libc::waitid(libc::P_PID, child.id() as libc::id_t, &mut info, flags) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { info.si_pid() } != 0)
+}
+
+// SIGTERM an die ganze Gruppe, nach einer Schonfrist SIGKILL an alles, was noch übrig ist
+pub fn kill_group(child: &mut Child) {
+    signal_group(child, libc::SIGTERM);
+    let deadline = Instant::now() + KILL_GRACE;
+    while Instant::now() < deadline && !leader_exited(child).unwrap_or(true) {
+        thread::sleep(Duration::from_millis(20));
+    }
+    signal_group(child, libc::SIGKILL);
+    let _ = child.wait();
+}
+
+// Nach dem regulären Ende (laut `leader_exited`): Hintergrundprozesse, die noch eine Pipe offen halten,
+// nicht weiterlaufen lassen; erst danach wird der Anführer abgeholt
+pub fn kill_leftovers(child: &mut Child) -> io::Result<ExitStatus> {
+    signal_group(child, libc::SIGKILL);
+    child.wait()
+}
+
+// Wie Command::output, aber mit Zeitlimit: Programme wie `foo --help` können hängen oder auf Eingabe warten.
+// Pager werden abgeschaltet, damit z. B. `git help -a` direkt ausgibt.
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> io::Result<Output> {
+    let mut child = spawn_in_group(
+        command
+            .env("PAGER", "cat")
+            .env("GIT_PAGER", "cat")
+            .env("MANPAGER", "cat")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped()),
+    )?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if leader_exited(&child)? {
+            break;
+        }
+        if Instant::now() >= deadline {
+            kill_group(&mut child);
+            let program = command.get_program().to_string_lossy().to_string();
+            return Err(io::Error::new(io::ErrorKind::TimedOut, format!("{} did not finish within {:?}", program, timeout)));
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    let status = kill_leftovers(&mut child)?;
+    // Ein Enkelprozess mit eigener Sitzung könnte die Pipe geerbt haben; dann nicht endlos warten
+    Ok(Output {
+        status,
+        stdout: stdout.recv_timeout(READ_GRACE).unwrap_or_default(),
+        stderr: stderr.recv_timeout(READ_GRACE).unwrap_or_default(),
+    })
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        let _ = sender.send(buffer);
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_temp_dirs_are_fresh_and_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let first = private_temp_dir("cli_organizer_test").unwrap();
+        let second = private_temp_dir("cli_organizer_test").unwrap();
+        let mode = fs::metadata(&first).unwrap().permissions().mode();
+        let _ = fs::remove_dir(&first);
+        let _ = fs::remove_dir(&second);
+        assert_ne!(first, second);
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn timeout_kills_the_pipeline() {
+        let started = Instant::now();
+        let result = output_with_timeout(Command::new("bash").args(["-c", "sleep 2718 | cat"]), Duration::from_millis(300));
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn background_child_holding_the_pipe_does_not_block() {
+        let started = Instant::now();
+        let output = output_with_timeout(Command::new("bash").args(["-c", "echo ready; sleep 2719 &"]), Duration::from_secs(5)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "ready\n");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,432 @@
+use crate::app::MyApp;
+use crate::config;
+use crate::explain::program_names;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use crate::process::{kill_group, kill_leftovers, leader_exited, private_temp_dir, spawn_in_group};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const RUN_SETTINGS_KEY: &str = "cli_organizer_run_settings";
+const RUN_LOG_FILE: &str = "cli_organizer_run_log.json";
+const MAX_LOG_ENTRIES_PER_COMMAND: usize = 20;
+const MAX_LOGGED_OUTPUT: usize = 4 * 1024;
+// So lange wird nach dem Ende auf die letzte Ausgabe der Lesethreads gewartet
+const DRAIN_GRACE: Duration = Duration::from_millis(200);
+const READ_CHUNK: usize = 8 * 1024;
+
+// Nur diese Variablen werden an den Prozess weitergereicht, HOME zeigt auf das Temp-Verzeichnis
+const ENV_ALLOW_LIST: [&str; 6] = ["PATH", "LANG", "LC_ALL", "TERM", "USER", "TZ"];
+
+const DESTRUCTIVE_PROGRAMS: [&str; 16] = [
+    "rm", "rmdir", "dd", "shred", "wipefs", "fdisk", "sfdisk", "parted", "mkswap", "truncate",
+    "sudo", "su", "doas", "reboot", "shutdown", "poweroff",
+];
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunSettings {
+    pub enabled: bool,
+    pub timeout_secs: u64,
+    pub output_limit_kb: usize,
+}
+
+impl Default for RunSettings {
+    fn default() -> Self {
+        Self { enabled: false, timeout_secs: 10, output_limit_kb: 256 }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub command_line: String,
+    pub started_at: u64,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub output: String,
+}
+
+pub enum RunEvent {
+    Stdout(String),
+    Stderr(String),
+    Truncated,
+    Finished { exit_code: Option<i32>, timed_out: bool },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+    Info,
+}
+
+pub struct RunningCommand {
+    pub command_line: String,
+    pub started_at: u64,
+    pub receiver: Receiver<RunEvent>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+// rm, dd, mkfs.* usw. irgendwo in der Pipeline erfordern eine Bestätigung
+pub fn destructive_programs(command_line: &str) -> Vec<String> {
+    program_names(command_line)
+        .into_iter()
+        .filter(|program| {
+            let name = program.rsplit('/').next().unwrap_or(program);
+            DESTRUCTIVE_PROGRAMS.contains(&name) || name.starts_with("mkfs")
+        })
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+// stdout und stderr teilen sich ein gemeinsames Ausgabelimit. Gelesen wird in festen Blöcken, damit auch eine
+// Ausgabe ohne Zeilenumbruch (`yes | tr -d '\n'`) nicht mehr als das Limit im Speicher belegt. Ist es erreicht,
+// setzt der Lesethread `stop` und die Prozessgruppe wird beendet. Ungültiges UTF-8 wird ersetzt statt abzubrechen.
+fn stream_lines<R: Read + Send + 'static>(
+    mut reader: R,
+    sender: Sender<RunEvent>,
+    used: Arc<AtomicUsize>,
+    limit: usize,
+    stop: Arc<AtomicBool>,
+    wrap: fn(String) -> RunEvent,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let send_line = |line: &[u8]| {
+            let line = String::from_utf8_lossy(line);
+            // Ist das Panel schon geschlossen, trotzdem weiterlesen
+            let _ = sender.send(wrap(line.trim_end_matches(['\n', '\r']).to_string()));
+        };
+        let mut chunk = [0; READ_CHUNK];
+        let mut pending = Vec::new();
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    debug!("Stopped reading command output: {}", e);
+                    break;
+                }
+            };
+            let before = used.fetch_add(read, Ordering::Relaxed);
+            let fits = read.min(limit.saturating_sub(before));
+            pending.extend_from_slice(&chunk[..fits]);
+            while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                send_line(&line);
+            }
+            if fits < read {
+                if before <= limit {
+                    let _ = sender.send(RunEvent::Truncated);
+                }
+                stop.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+        if !pending.is_empty() {
+            send_line(&pending);
+        }
+    })
+}
+
+pub fn spawn(command_line: &str, settings: &RunSettings) -> std::io::Result<RunningCommand> {
+    let dir = private_temp_dir("cli_organizer_run")?;
+    debug!("Running {:?} in {}", command_line, dir.display());
+
+    let mut command = Command::new("bash");
+    command
+        .arg("-c")
+        .arg(command_line)
+        .current_dir(&dir)
+        .env_clear()
+        .env("HOME", &dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for key in ENV_ALLOW_LIST {
+        if let Ok(value) = std::env::var(key) {
+            command.env(key, value);
+        }
+    }
+    let mut child = spawn_in_group(&mut command)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let used = Arc::new(AtomicUsize::new(0));
+    let limit = settings.output_limit_kb * 1024;
+    // Stopp-Knopf, Zeitlimit und Ausgabelimit beenden den Prozess auf demselben Weg
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_flag = cancel.clone();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(stream_lines(stdout, sender.clone(), used.clone(), limit, cancel.clone(), RunEvent::Stdout));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(stream_lines(stderr, sender.clone(), used, limit, cancel.clone(), RunEvent::Stderr));
+    }
+
+    let timeout = Duration::from_secs(settings.timeout_secs);
+    thread::spawn(move || {
+        let deadline = Instant::now() + timeout;
+        let (exit_code, timed_out) = loop {
+            match leader_exited(&child) {
+                // Hintergrundjobs enden mit der Shell, wie beim Abbruch
+                Ok(true) => match kill_leftovers(&mut child) {
+                    Ok(status) => break (status.code(), false),
+                    Err(e) => {
+                        error!("Failed to wait for command: {}", e);
+                        break (None, false);
+                    }
+                },
+                Ok(false) if Instant::now() >= deadline || cancel_flag.load(Ordering::Relaxed) => {
+                    kill_group(&mut child);
+                    break (None, true);
+                }
+                Ok(false) => thread::sleep(Duration::from_millis(50)),
+                Err(e) => {
+                    error!("Failed to wait for command: {}", e);
+                    break (None, false);
+                }
+            }
+        };
+        // Restausgabe vor der Abschlussmeldung, aber nicht endlos: ein Prozess mit eigener Sitzung könnte die Pipe halten
+        let drain_deadline = Instant::now() + DRAIN_GRACE;
+        while readers.iter().any(|reader| !reader.is_finished()) && Instant::now() < drain_deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            error!("Failed to remove {}: {}", dir.display(), e);
+        }
+        let _ = sender.send(RunEvent::Finished { exit_code, timed_out });
+    });
+
+    Ok(RunningCommand { command_line: command_line.to_string(), started_at: now_secs(), receiver, cancel })
+}
+
+impl MyApp {
+    pub fn open_run_panel(&mut self, command_line: Option<&str>) {
+        self.run_open = true;
+        if let Some(command_line) = command_line {
+            self.run_input = command_line.to_string();
+        } else if self.run_input.is_empty() {
+            self.run_input = self.selected_command.clone().unwrap_or_default();
+        }
+        self.run_needs_confirmation = false;
+    }
+
+    pub fn start_run(&mut self, confirmed: bool) {
+        let command_line = self.run_input.trim().to_string();
+        if command_line.is_empty() || self.running_command.is_some() || !self.run_settings.enabled {
+            return;
+        }
+        if !confirmed && !destructive_programs(&command_line).is_empty() {
+            self.run_needs_confirmation = true;
+            return;
+        }
+        self.run_needs_confirmation = false;
+        self.run_output.clear();
+        match spawn(&command_line, &self.run_settings) {
+            Ok(running) => self.running_command = Some(running),
+            Err(e) => self.run_output.push((OutputStream::Info, format!("Start fehlgeschlagen: {}", e))),
+        }
+    }
+
+    pub fn stop_run(&self) {
+        if let Some(running) = &self.running_command {
+            running.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // Wird jeden Frame aufgerufen und übernimmt neue Ausgabezeilen aus den Lesethreads
+    pub fn poll_run(&mut self) -> bool {
+        let Some(running) = &self.running_command else {
+            return false;
+        };
+        let mut finished = None;
+        while let Ok(event) = running.receiver.try_recv() {
+            match event {
+                RunEvent::Stdout(line) => self.run_output.push((OutputStream::Stdout, line)),
+                RunEvent::Stderr(line) => self.run_output.push((OutputStream::Stderr, line)),
+                RunEvent::Truncated => self.run_output.push((OutputStream::Info, "… Ausgabe gekürzt".to_string())),
+                RunEvent::Finished { exit_code, timed_out } => finished = Some((exit_code, timed_out)),
+            }
+        }
+        if let Some((exit_code, timed_out)) = finished {
+            let status = match (exit_code, timed_out) {
+                (_, true) => "Abgebrochen (Zeitlimit, Ausgabelimit oder Stopp)".to_string(),
+                (Some(code), _) => format!("Beendet mit Code {}", code),
+                (None, _) => "Beendet durch Signal".to_string(),
+            };
+            self.run_output.push((OutputStream::Info, status));
+            if let Some(running) = self.running_command.take() {
+                self.record_run(running, exit_code, timed_out);
+            }
+        }
+        true
+    }
+
+    fn record_run(&mut self, running: RunningCommand, exit_code: Option<i32>, timed_out: bool) {
+        let mut output: String = self.run_output
+            .iter()
+            .map(|(_, line)| line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if output.len() > MAX_LOGGED_OUTPUT {
+            let mut end = MAX_LOGGED_OUTPUT;
+            while !output.is_char_boundary(end) {
+                end -= 1;
+            }
+            output.truncate(end);
+        }
+        let program = program_names(&running.command_line).into_iter().next().unwrap_or_default();
+        let log = self.run_log.entry(program).or_default();
+        log.push(RunRecord {
+            command_line: running.command_line,
+            started_at: running.started_at,
+            exit_code,
+            timed_out,
+            output,
+        });
+        if log.len() > MAX_LOG_ENTRIES_PER_COMMAND {
+            log.remove(0);
+        }
+        self.save_run_log();
+    }
+
+    pub fn load_run_log(&mut self) {
+        if let Some(run_log) = config::load_json::<HashMap<String, Vec<RunRecord>>>(RUN_LOG_FILE) {
+            self.run_log = run_log;
+        }
+    }
+
+    fn save_run_log(&self) {
+        config::save_json(RUN_LOG_FILE, &self.run_log);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(timeout_secs: u64) -> RunSettings {
+        RunSettings { enabled: true, timeout_secs, output_limit_kb: 64 }
+    }
+
+    fn collect(running: RunningCommand) -> (Vec<String>, bool, Option<i32>) {
+        let mut lines = Vec::new();
+        let mut truncated = false;
+        loop {
+            match running.receiver.recv_timeout(Duration::from_secs(20)).expect("command finishes") {
+                RunEvent::Stdout(line) | RunEvent::Stderr(line) => lines.push(line),
+                RunEvent::Truncated => truncated = true,
+                RunEvent::Finished { exit_code, .. } => return (lines, truncated, exit_code),
+            }
+        }
+    }
+
+    fn processes_with_argument(argument: &str) -> usize {
+        fs::read_dir("/proc")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| fs::read(entry.path().join("cmdline")).ok())
+            .filter(|cmdline| cmdline.split(|byte| *byte == 0).any(|part| part == argument.as_bytes()))
+            .count()
+    }
+
+    fn flags_rm(command_line: &str) -> bool {
+        destructive_programs(command_line).iter().any(|program| program.ends_with("rm"))
+    }
+
+    #[test]
+    fn destructive_programs_in_groups_and_compound_commands() {
+        assert!(flags_rm("(rm -rf x)"));
+        assert!(flags_rm("{ rm x; }"));
+        assert!(flags_rm("if true; then rm -rf x; fi"));
+        assert!(flags_rm("for f in *; do rm $f; done"));
+        assert!(flags_rm("ls && /bin/rm x"));
+        assert!(!flags_rm("echo rm; ls -l"));
+    }
+
+    #[test]
+    fn destructive_programs_behind_wrappers() {
+        assert!(flags_rm("ls | xargs rm"));
+        assert!(flags_rm("xargs -I {} rm {}"));
+        assert!(flags_rm("env FOO=1 rm x"));
+        assert!(flags_rm("sudo -u root rm -rf x"));
+        assert!(flags_rm("env -u HOME -C /tmp rm x"));
+        assert!(flags_rm("xargs -n 1 -I {} rm {}"));
+        assert!(flags_rm("nice -n 10 rm x"));
+        assert!(flags_rm("time rm x"));
+        assert!(flags_rm("command rm x"));
+        assert!(flags_rm("exec rm x"));
+        assert!(flags_rm("timeout 5 rm x"));
+        assert!(flags_rm("find . -name '*.o' -exec rm {} \\;"));
+    }
+
+    #[test]
+    fn destructive_programs_in_scripts_and_substitutions() {
+        assert!(flags_rm("bash -c 'rm -rf x'"));
+        assert!(flags_rm("sh -ec \"ls; rm x\""));
+        assert!(flags_rm("eval 'rm x'"));
+        assert!(flags_rm("echo $(rm x)"));
+        assert!(flags_rm("echo \"$(rm x)\""));
+        assert!(flags_rm("echo `rm x`"));
+        assert!(flags_rm("x=$(rm y)"));
+    }
+
+    #[test]
+    fn descriptor_redirections_are_not_commands() {
+        assert_eq!(crate::explain::program_names("echo fehler >&2"), ["echo"]);
+        assert_eq!(crate::explain::program_names("cat <&3 2>&2 &>> log"), ["cat"]);
+        assert!(flags_rm("echo x >&2 && rm x"));
+    }
+
+    #[test]
+    fn timeout_stops_the_whole_pipeline() {
+        let running = spawn("sleep 3141 | cat & sleep 3141; wait", &settings(1)).unwrap();
+        let (_, _, exit_code) = collect(running);
+        assert_eq!(exit_code, None);
+        assert_eq!(processes_with_argument("3141"), 0);
+    }
+
+    #[test]
+    fn invalid_utf8_does_not_stop_reading() {
+        let running = spawn(r"printf 'a\377b\n'; seq 1 3", &settings(5)).unwrap();
+        let (lines, truncated, exit_code) = collect(running);
+        assert_eq!(lines, ["a\u{FFFD}b", "1", "2", "3"]);
+        assert!(!truncated);
+        assert_eq!(exit_code, Some(0));
+    }
+
+    #[test]
+    fn output_limit_stops_the_command() {
+        // 64 KB Limit, etwa 600 KB Ausgabe
+        let started = Instant::now();
+        let running = spawn("seq 1 100000; sleep 3142", &settings(15)).unwrap();
+        let (lines, truncated, exit_code) = collect(running);
+        assert!(truncated);
+        assert_eq!(exit_code, None);
+        assert!(lines.len() < 100000);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(processes_with_argument("3142"), 0);
+    }
+
+    #[test]
+    fn output_without_newlines_stays_within_the_limit() {
+        let started = Instant::now();
+        let running = spawn("yes | tr -d '\\n'", &settings(15)).unwrap();
+        let (lines, truncated, _) = collect(running);
+        assert!(truncated);
+        assert!(lines.iter().map(String::len).sum::<usize>() <= 64 * 1024);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -1,36 +1,105 @@
-use std::fs;
-use serde_json;
-use crate::my_app::MyApp;
+use crate::app::MyApp;
+use crate::config;
+
+const SEARCH_HISTORY_FILE: &str = "cli_organizer_search_history.json";
+const MAX_SEARCH_HISTORY: usize = 100;
+const MAX_HISTORY_SUGGESTIONS: usize = 6;
+const MAX_COMMAND_SUGGESTIONS: usize = 6;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SuggestionKind {
+    History,
+    Command,
+}
+
+#[derive(Clone)]
+pub struct SearchSuggestion {
+    pub text: String,
+    pub kind: SuggestionKind,
+}
 
 impl MyApp {
-    pub fn add_to_search_history(&mut self, query: String) {
-        if !self.search_history.contains(&query) {
-            self.search_history.push(query);
-            self.save_search_history();
+    // Der neueste Eintrag steht am Ende; doppelte Suchen werden nach hinten verschoben
+    pub fn add_to_search_history(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
         }
+        self.search_history.retain(|entry| entry != query);
+        self.search_history.push(query.to_string());
+        self.suggestion_cache = None;
+        if self.search_history.len() > MAX_SEARCH_HISTORY {
+            let overflow = self.search_history.len() - MAX_SEARCH_HISTORY;
+            self.search_history.drain(..overflow);
+        }
+        self.save_search_history();
     }
 
-    pub fn save_search_history(&self) {
-        let path = dirs::config_dir().unwrap().join("cli_manpage_history.json");
-        if let Ok(json) = serde_json::to_string(&self.search_history) {
-            if let Err(e) = fs::write(&path, json) {
-                println!("Failed to save search history: {}", e);
+    pub fn remove_from_search_history(&mut self, query: &str) {
+        self.search_history.retain(|entry| entry != query);
+        self.suggestion_cache = None;
+        self.save_search_history();
+    }
+
+    // Der Katalog wird nur durchsucht, wenn sich die Eingabe seit dem letzten Aufruf geändert hat
+    pub fn search_suggestions(&mut self) -> Vec<SearchSuggestion> {
+        match &self.suggestion_cache {
+            Some((query, suggestions)) if *query == self.search_query => suggestions.clone(),
+            _ => {
+                let suggestions = self.compute_search_suggestions();
+                self.suggestion_cache = Some((self.search_query.clone(), suggestions.clone()));
+                suggestions
             }
         }
     }
 
+    // Erst passende frühere Suchen (neueste zuerst), danach Befehle aus dem Katalog, die mit der Eingabe beginnen
+    fn compute_search_suggestions(&self) -> Vec<SearchSuggestion> {
+        let query = self.search_query.as_str();
+        let mut suggestions: Vec<SearchSuggestion> = self.search_history
+            .iter()
+            .rev()
+            .filter(|entry| entry.contains(query) && *entry != query)
+            .take(MAX_HISTORY_SUGGESTIONS)
+            .map(|entry| SearchSuggestion { text: entry.clone(), kind: SuggestionKind::History })
+            .collect();
+
+        if !query.is_empty() {
+            let mut commands: Vec<&String> = self.commands
+                .iter()
+                .filter(|cmd| cmd.starts_with(query) && *cmd != query)
+                .filter(|cmd| !suggestions.iter().any(|s| &s.text == *cmd))
+                .collect();
+            commands.sort_by_key(|cmd| cmd.len());
+            suggestions.extend(commands
+                .into_iter()
+                .take(MAX_COMMAND_SUGGESTIONS)
+                .map(|cmd| SearchSuggestion { text: cmd.clone(), kind: SuggestionKind::Command }));
+        }
+        suggestions
+    }
+
+    pub fn apply_search_suggestion(&mut self, query: &str) {
+        self.search_query = query.to_string();
+        self.search_suggestion_index = None;
+        self.filter_commands();
+        self.add_to_search_history(query);
+    }
+
+    pub fn clear_search_history(&mut self) {
+        self.search_history.clear();
+        self.suggestion_cache = None;
+        self.save_search_history();
+    }
+
     pub fn load_search_history(&mut self) {
-        let path = dirs::config_dir().unwrap().join("cli_manpage_history.json");
-        if path.exists() {
-            if let Ok(json) = fs::read_to_string(&path) {
-                if let Ok(history) = serde_json::from_str::<Vec<String>>(&json) {
-                    self.search_history = history;
-                } else {
-                    println!("Failed to parse search history");
-                }
-            } else {
-                println!("Failed to read search history");
-            }
+        if let Some(history) = config::load_json(SEARCH_HISTORY_FILE) {
+            self.search_history = history;
+            self.suggestion_cache = None;
         }
     }
-}
\ No newline at end of file
+
+    pub fn save_search_history(&self) {
+        config::save_json(SEARCH_HISTORY_FILE, &self.search_history);
+    }
+}
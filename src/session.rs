@@ -0,0 +1,35 @@
+use crate::tabs::ManpageTab;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const SESSION_KEY: &str = "cli_organizer_session";
+pub const NAMED_SESSIONS_KEY: &str = "cli_organizer_named_sessions";
+
+// Zustand, der zwischen zwei Starts wiederhergestellt wird.
+// Panelbreiten und die Fenstergeometrie speichert eframe selbst (egui-Memory bzw. persist_window).
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub selected_command: Option<String>,
+    pub search_query: String,
+    pub manpage_search_query: String,
+    pub manpage_scroll_offset: f32,
+    pub window_size: Option<[f32; 2]>,
+    // Ältere Sitzungen ohne Tabs öffnen nur den gewählten Befehl
+    #[serde(default)]
+    pub tabs: Vec<ManpageTab>,
+    #[serde(default)]
+    pub active_tab: usize,
+    #[serde(default)]
+    pub split_tab: Option<usize>,
+}
+
+pub fn load(storage: &dyn eframe::Storage) -> (Option<Session>, BTreeMap<String, Session>) {
+    let session = eframe::get_value(storage, SESSION_KEY);
+    let named = eframe::get_value(storage, NAMED_SESSIONS_KEY).unwrap_or_default();
+    (session, named)
+}
+
+pub fn save(storage: &mut dyn eframe::Storage, session: &Session, named: &BTreeMap<String, Session>) {
+    eframe::set_value(storage, SESSION_KEY, session);
+    eframe::set_value(storage, NAMED_SESSIONS_KEY, named);
+}
@@ -0,0 +1,83 @@
+use super::{lines, parse_functions, run, split_entries, ShellBackend, ShellEntry, ShellKind, FUNCTIONS_MARKER};
+use log::error;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Verzeichnisse, aus denen bash-completion Vervollständigungen bei Bedarf nachlädt
+const COMPLETION_DIRS: [&str; 3] = [
+    "/usr/share/bash-completion/completions",
+    "/usr/local/share/bash-completion/completions",
+    "/etc/bash_completion.d",
+];
+
+pub struct Bash;
+
+pub fn completion_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(data_dir) = dirs::data_dir() {
+        dirs.push(data_dir.join("bash-completion/completions"));
+    }
+    dirs.extend(COMPLETION_DIRS.iter().map(PathBuf::from));
+    dirs
+}
+
+impl ShellBackend for Bash {
+    fn kind(&self) -> ShellKind {
+        ShellKind::Bash
+    }
+
+    fn commands(&self) -> io::Result<Vec<String>> {
+        run(ShellKind::Bash, &["-c"], "compgen -c", &[]).map(|output| lines(&output))
+    }
+
+    fn builtins(&self) -> io::Result<Vec<String>> {
+        run(ShellKind::Bash, &["-c"], "compgen -b", &[]).map(|output| lines(&output))
+    }
+
+    fn interactive_entries(&self) -> io::Result<Vec<ShellEntry>> {
+        let script = format!("alias -p; echo {}; declare -f", FUNCTIONS_MARKER);
+        let output = run(ShellKind::Bash, &["-lic"], &script, &[])?;
+        let (mut entries, functions) = split_entries(ShellKind::Bash, &output, FUNCTIONS_MARKER);
+        entries.extend(parse_functions(ShellKind::Bash, &functions));
+        Ok(entries)
+    }
+
+    // Bereits registrierte Vervollständigungen ("complete -F _git git") plus die nachladbaren Dateien
+    fn completions(&self) -> io::Result<Vec<String>> {
+        // Hängt die Login-Shell, bleiben immer noch die Dateien von bash-completion
+        let output = run(ShellKind::Bash, &["-lic"], "complete -p", &[]).unwrap_or_else(|e| {
+            error!("Failed to query registered bash completions: {}", e);
+            String::new()
+        });
+        let mut names: Vec<String> = output
+            .lines()
+            .filter(|line| line.starts_with("complete "))
+            .filter_map(|line| line.split_whitespace().last())
+            .map(str::to_string)
+            .collect();
+        for dir in completion_dirs() {
+            let Ok(read_dir) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Dateien mit "_" am Anfang sind veraltete Varianten, die bash-completion überspringt
+                if !name.starts_with('_') && !name.starts_with('.') {
+                    names.push(name);
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    fn function_definition(&self, name: &str) -> io::Result<String> {
+        run(ShellKind::Bash, &["-lic"], "declare -f \"$1\"", &["_", name])
+    }
+
+    fn builtin_help(&self, name: &str) -> io::Result<String> {
+        run(ShellKind::Bash, &["-c"], "help -m \"$1\"", &["_", name])
+    }
+}
@@ -0,0 +1,74 @@
+use super::{lines, run, split_entries, EntryKind, ShellBackend, ShellEntry, ShellKind, FUNCTIONS_MARKER};
+use std::io;
+use std::path::PathBuf;
+
+// Standardwerte von $fish_complete_path, in der Reihenfolge, in der fish sie durchsucht
+const COMPLETION_DIRS: [&str; 4] = [
+    "/etc/fish/completions",
+    "/usr/local/share/fish/vendor_completions.d",
+    "/usr/share/fish/vendor_completions.d",
+    "/usr/share/fish/completions",
+];
+
+pub struct Fish;
+
+// Funktioniert auch ohne installiertes fish, da viele Pakete ihre fish-Vervollständigungen trotzdem mitliefern
+pub fn completion_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(config_dir) = dirs::config_dir() {
+        dirs.push(config_dir.join("fish/completions"));
+    }
+    if let Some(data_dir) = dirs::data_dir() {
+        dirs.push(data_dir.join("fish/vendor_completions.d"));
+    }
+    dirs.extend(COMPLETION_DIRS.iter().map(PathBuf::from));
+    dirs
+}
+
+impl ShellBackend for Fish {
+    fn kind(&self) -> ShellKind {
+        ShellKind::Fish
+    }
+
+    // fish hat kein Gegenstück zu compgen -c, daher werden die PATH-Verzeichnisse direkt durchsucht
+    fn commands(&self) -> io::Result<Vec<String>> {
+        let script = "for dir in $PATH; for file in $dir/*; if test -x $file -a ! -d $file; basename $file; end; end; end";
+        run(ShellKind::Fish, &["-c"], script, &[]).map(|output| lines(&output))
+    }
+
+    fn builtins(&self) -> io::Result<Vec<String>> {
+        run(ShellKind::Fish, &["-c"], "builtin -n", &[]).map(|output| lines(&output))
+    }
+
+    // fish listet nur Funktionsnamen; die Definition wird bei Bedarf nachgeladen
+    fn interactive_entries(&self) -> io::Result<Vec<ShellEntry>> {
+        let script = format!("alias; echo {}; functions -n", FUNCTIONS_MARKER);
+        let output = run(ShellKind::Fish, &["-l", "-c"], &script, &[])?;
+        let (mut entries, functions) = split_entries(ShellKind::Fish, &output, FUNCTIONS_MARKER);
+        let aliases: Vec<String> = entries.iter().map(|entry| entry.name.clone()).collect();
+        for name in functions.split([',', '\n']).map(str::trim) {
+            // Aliase sind in fish ebenfalls Funktionen und stehen deshalb in beiden Listen
+            if name.is_empty() || name.starts_with('_') || aliases.iter().any(|alias| alias == name) {
+                continue;
+            }
+            entries.push(ShellEntry::new(name, EntryKind::Function, ShellKind::Fish, None));
+        }
+        Ok(entries)
+    }
+
+    fn completions(&self) -> io::Result<Vec<String>> {
+        let script = "for dir in $fish_complete_path; for file in $dir/*.fish; basename $file .fish; end; end";
+        let mut names = run(ShellKind::Fish, &["-l", "-c"], script, &[]).map(|output| lines(&output))?;
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    fn function_definition(&self, name: &str) -> io::Result<String> {
+        run(ShellKind::Fish, &["-l", "-c"], "functions $argv[1]", &[name])
+    }
+
+    fn builtin_help(&self, name: &str) -> io::Result<String> {
+        run(ShellKind::Fish, &["-c"], "__fish_print_help $argv[1]", &[name])
+    }
+}
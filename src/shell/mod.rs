@@ -0,0 +1,224 @@
+mod bash;
+mod fish;
+mod zsh;
+
+pub use bash::completion_dirs as bash_completion_dirs;
+pub use fish::completion_dirs as fish_completion_dirs;
+
+use crate::process::output_with_timeout;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+pub const SHELL_KEY: &str = "cli_organizer_shell";
+const FUNCTIONS_MARKER: &str = "__CLI_ORGANIZER_FUNCTIONS__";
+// Eine .bashrc, die auf das Terminal wartet (`read`, `exec tmux`), darf die Abfrage nicht ewig offen halten
+const SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum ShellKind {
+    #[default]
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryKind {
+    Builtin,
+    Alias,
+    Function,
+}
+
+impl EntryKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EntryKind::Builtin => "builtin",
+            EntryKind::Alias => "alias",
+            EntryKind::Function => "function",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ShellEntry {
+    pub name: String,
+    pub kind: EntryKind,
+    pub shell: ShellKind,
+    pub definition: Option<String>,
+}
+
+// Eine Shell als Quelle für Befehle und deren Dokumentation.
+// Jede Shell listet ihre Befehle, Aliase und Vervollständigungen auf eigene Weise auf.
+pub trait ShellBackend: Sync {
+    fn kind(&self) -> ShellKind;
+    // Alle Befehle, die die Shell über PATH findet
+    fn commands(&self) -> io::Result<Vec<String>>;
+    fn builtins(&self) -> io::Result<Vec<String>>;
+    // Aliase und Funktionen aus .bashrc/.zshrc/config.fish; startet die Login-Shell des Benutzers
+    fn interactive_entries(&self) -> io::Result<Vec<ShellEntry>>;
+    // Befehle, für die die Shell eine Vervollständigung kennt
+    fn completions(&self) -> io::Result<Vec<String>>;
+    fn function_definition(&self, name: &str) -> io::Result<String>;
+    fn builtin_help(&self, name: &str) -> io::Result<String>;
+}
+
+// Ergebnis der langsamen Abfragen, die im Hintergrund laufen
+pub struct Discovery {
+    pub entries: Vec<ShellEntry>,
+    pub completions: Vec<String>,
+}
+
+impl ShellEntry {
+    pub fn new(name: &str, kind: EntryKind, shell: ShellKind, definition: Option<String>) -> Self {
+        Self { name: name.to_string(), kind, shell, definition }
+    }
+
+    // Das Programm, auf das ein Alias zeigt: "alias ll='ls -alF'" -> "ls"
+    pub fn alias_target(&self) -> Option<String> {
+        if self.kind != EntryKind::Alias {
+            return None;
+        }
+        let definition = self.definition.as_deref()?;
+        let value = match self.shell {
+            ShellKind::Fish => definition.strip_prefix("alias ")?.split_once(char::is_whitespace)?.1,
+            _ => definition.split_once('=')?.1,
+        };
+        let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+        value.split_whitespace().next().map(str::to_string)
+    }
+
+    // Aufbereitet wie eine Manpage, damit Suche und Hervorhebung gleich funktionieren
+    pub fn documentation(&self, definition: &str) -> String {
+        let heading = match self.kind {
+            EntryKind::Alias => "ALIAS",
+            EntryKind::Function => "FUNCTION",
+            EntryKind::Builtin => "BUILTIN",
+        };
+        let body: Vec<String> = definition.lines().map(|line| format!("       {}", line)).collect();
+        format!("{} ({})\n\n{}\n{}\n", self.name, self.shell.name(), heading, body.join("\n"))
+    }
+}
+
+impl ShellKind {
+    pub const ALL: [ShellKind; 3] = [ShellKind::Bash, ShellKind::Zsh, ShellKind::Fish];
+
+    // Aus $SHELL, z. B. /usr/bin/zsh; unbekannte Shells werden wie bash behandelt
+    pub fn detect() -> Self {
+        let shell = std::env::var("SHELL").unwrap_or_default();
+        match shell.rsplit('/').next().unwrap_or_default() {
+            "zsh" => ShellKind::Zsh,
+            "fish" => ShellKind::Fish,
+            _ => ShellKind::Bash,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ShellKind::Bash => "bash",
+            ShellKind::Zsh => "zsh",
+            ShellKind::Fish => "fish",
+        }
+    }
+
+    pub fn backend(self) -> &'static dyn ShellBackend {
+        match self {
+            ShellKind::Bash => &bash::Bash,
+            ShellKind::Zsh => &zsh::Zsh,
+            ShellKind::Fish => &fish::Fish,
+        }
+    }
+
+    pub fn is_installed(self) -> bool {
+        Command::new(self.name())
+            .arg("-c")
+            .arg("true")
+            .stdin(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    pub fn discover(self) -> Discovery {
+        let backend = self.backend();
+        let entries = backend.interactive_entries().unwrap_or_else(|e| {
+            error!("Failed to query {} aliases and functions: {}", self.name(), e);
+            Vec::new()
+        });
+        let completions = backend.completions().unwrap_or_else(|e| {
+            error!("Failed to list {} completions: {}", self.name(), e);
+            Vec::new()
+        });
+        debug!(
+            "Found {} aliases and functions and {} completions in {}",
+            entries.len(),
+            completions.len(),
+            self.name()
+        );
+        Discovery { entries, completions }
+    }
+}
+
+// Führt `script` mit der jeweiligen Shell aus; `extra_args` landen als Positionsparameter dahinter.
+// Die Shell läuft in einer eigenen Prozessgruppe ohne Terminal und wird nach Ablauf der Zeit samt Kindern beendet.
+fn run(shell: ShellKind, args: &[&str], script: &str, extra_args: &[&str]) -> io::Result<String> {
+    let output = output_with_timeout(
+        Command::new(shell.name()).args(args).arg(script).args(extra_args),
+        SHELL_TIMEOUT,
+    )?;
+    if output.status.success() || !output.stdout.is_empty() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(io::Error::other(String::from_utf8_lossy(&output.stderr).to_string()))
+    }
+}
+
+fn lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// bash: alias ll='ls -alF'   zsh: alias ll='ls -alF' bzw. alias ll=ls   fish: alias ll 'ls -alF'
+fn parse_alias(shell: ShellKind, line: &str) -> Option<ShellEntry> {
+    let rest = line.strip_prefix("alias ")?;
+    let rest = rest.strip_prefix("-- ").unwrap_or(rest);
+    let name = match shell {
+        ShellKind::Fish => rest.split_whitespace().next()?,
+        _ => rest.split_once('=')?.0,
+    };
+    Some(ShellEntry::new(name, EntryKind::Alias, shell, Some(line.to_string())))
+}
+
+// bash/zsh: "name () " bzw. "name () {" in Spalte 0, Ende mit "}" in Spalte 0
+fn parse_functions(shell: ShellKind, output: &str) -> Vec<ShellEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    for line in output.lines() {
+        if let Some((name, body)) = current.as_mut() {
+            body.push(line);
+            if line == "}" {
+                entries.push(ShellEntry::new(name, EntryKind::Function, shell, Some(body.join("\n"))));
+                current = None;
+            }
+            continue;
+        }
+        if let Some(name) = line.strip_suffix(" () ").or_else(|| line.strip_suffix(" () {")) {
+            if !name.starts_with(char::is_whitespace) && !name.is_empty() {
+                current = Some((name.to_string(), vec![line]));
+            }
+        }
+    }
+    entries
+}
+
+// Trennt die Ausgabe von "Aliase; echo MARKER; Funktionen" in beide Teile
+fn split_entries(shell: ShellKind, output: &str, marker: &str) -> (Vec<ShellEntry>, String) {
+    let (aliases, functions) = output.split_once(marker).unwrap_or((output, ""));
+    let entries = aliases.lines().filter_map(|line| parse_alias(shell, line)).collect();
+    (entries, functions.to_string())
+}
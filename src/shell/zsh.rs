@@ -0,0 +1,48 @@
+use super::{lines, parse_functions, run, split_entries, ShellBackend, ShellEntry, ShellKind, FUNCTIONS_MARKER};
+use crate::manpage::{extract_entry, get_manpage};
+use std::io;
+
+pub struct Zsh;
+
+impl ShellBackend for Zsh {
+    fn kind(&self) -> ShellKind {
+        ShellKind::Zsh
+    }
+
+    // $commands ist die Hash-Tabelle aller Programme in PATH
+    fn commands(&self) -> io::Result<Vec<String>> {
+        run(ShellKind::Zsh, &["-c"], "print -rl -- ${(k)commands}", &[]).map(|output| lines(&output))
+    }
+
+    fn builtins(&self) -> io::Result<Vec<String>> {
+        run(ShellKind::Zsh, &["-c"], "print -rl -- ${(k)builtins}", &[]).map(|output| lines(&output))
+    }
+
+    fn interactive_entries(&self) -> io::Result<Vec<ShellEntry>> {
+        let script = format!("alias -L; echo {}; functions", FUNCTIONS_MARKER);
+        let output = run(ShellKind::Zsh, &["-lic"], &script, &[])?;
+        let (mut entries, functions) = split_entries(ShellKind::Zsh, &output, FUNCTIONS_MARKER);
+        // Funktionen, die mit "_" beginnen, gehören zum Vervollständigungssystem
+        entries.extend(
+            parse_functions(ShellKind::Zsh, &functions)
+                .into_iter()
+                .filter(|entry| !entry.name.starts_with('_')),
+        );
+        Ok(entries)
+    }
+
+    // $_comps existiert erst nach compinit, das die meisten .zshrc-Dateien aufrufen
+    fn completions(&self) -> io::Result<Vec<String>> {
+        run(ShellKind::Zsh, &["-lic"], "print -rl -- ${(k)_comps}", &[]).map(|output| lines(&output))
+    }
+
+    fn function_definition(&self, name: &str) -> io::Result<String> {
+        run(ShellKind::Zsh, &["-lic"], "functions \"$1\"", &["_", name])
+    }
+
+    fn builtin_help(&self, name: &str) -> io::Result<String> {
+        // zshbuiltins(1) beschreibt alle Builtins in einer Seite
+        extract_entry(&get_manpage("zshbuiltins")?, name)
+            .ok_or_else(|| io::Error::other(format!("{} not found in zshbuiltins", name)))
+    }
+}
@@ -0,0 +1,268 @@
+use crate::completion::{self, SpecFile};
+use crate::manpage::{extract_entry, get_manpage, man_dirs, strip_compression};
+use crate::process::output_with_timeout;
+use log::{debug, error};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::process::Command;
+use std::time::Duration;
+
+// Werkzeuge, deren Dokumentation pro Unterbefehl vorliegt; ihre `--help`-Ausgabe wird zusätzlich ausgewertet
+const SUBCOMMAND_TOOLS: [&str; 9] = ["git", "docker", "podman", "kubectl", "cargo", "systemctl", "npm", "gh", "rustup"];
+// Werkzeuge, bei denen `<tool> help <sub>` die Hilfe zum Unterbefehl ausgibt, ohne etwas auszuführen
+const HELP_SUBCOMMAND_TOOLS: [&str; 6] = ["git", "docker", "podman", "kubectl", "cargo", "rustup"];
+const HELP_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Default)]
+pub struct SubcommandTree {
+    // "git" -> ["add", "rebase", ...], alphabetisch
+    pub children: HashMap<String, Vec<String>>,
+    // "git rebase" -> "Reapply commits on top of another base tip"
+    pub descriptions: HashMap<String, String>,
+}
+
+impl SubcommandTree {
+    fn add(&mut self, parent: &str, sub: &str, description: &str) {
+        let children = self.children.entry(parent.to_string()).or_default();
+        if !children.iter().any(|child| child == sub) {
+            children.push(sub.to_string());
+        }
+        if !description.is_empty() {
+            self.descriptions
+                .entry(format!("{} {}", parent, sub))
+                .or_insert_with(|| description.to_string());
+        }
+    }
+}
+
+// Sammelt Unterbefehle aus man-Seiten wie git-rebase(1), aus Vervollständigungsdateien und aus `--help`.
+// Läuft im Hintergrund, da dafür Programme gestartet und viele Dateien gelesen werden.
+pub fn discover(commands: &[String], completion_files: &HashMap<String, SpecFile>) -> SubcommandTree {
+    let mut tree = SubcommandTree::default();
+    let tools: Vec<&str> = SUBCOMMAND_TOOLS
+        .iter()
+        .copied()
+        .filter(|tool| commands.iter().any(|command| command == tool))
+        .collect();
+
+    for (parent, sub) in man_page_subcommands(&tools) {
+        tree.add(&parent, &sub, "");
+    }
+    for tool in &tools {
+        for (sub, description) in help_subcommands(tool) {
+            tree.add(tool, &sub, &description);
+        }
+    }
+    for (command, file) in completion_files {
+        let Some(spec) = completion::load(command, file) else {
+            continue;
+        };
+        for child in &spec.root.children {
+            tree.add(command, &child.name, &child.description);
+        }
+    }
+    for children in tree.children.values_mut() {
+        children.sort();
+    }
+    debug!("Found subcommands for {} commands", tree.children.len());
+    tree
+}
+
+// "git-rebase.1.gz" in man1 -> ("git", "rebase")
+fn man_page_subcommands(tools: &[&str]) -> BTreeSet<(String, String)> {
+    let mut found = BTreeSet::new();
+    for dir in man_dirs() {
+        let Ok(sections) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for section in sections.flatten().filter(|entry| entry.file_name().to_string_lossy().starts_with("man")) {
+            let Ok(pages) = fs::read_dir(section.path()) else {
+                continue;
+            };
+            for page in pages.flatten() {
+                found.extend(page_subcommands(&page.file_name().to_string_lossy(), tools));
+            }
+        }
+    }
+    found
+}
+
+fn page_subcommands<'a>(file_name: &'a str, tools: &'a [&str]) -> impl Iterator<Item = (String, String)> + 'a {
+    let page_name = strip_compression(file_name).rsplit_once('.').map(|(name, _)| name);
+    tools.iter().filter_map(move |tool| {
+        let sub = page_name?.strip_prefix(tool)?.strip_prefix('-')?;
+        (!sub.is_empty()).then(|| (tool.to_string(), sub.to_string()))
+    })
+}
+
+fn help_subcommands(tool: &str) -> Vec<(String, String)> {
+    let args: &[&str] = match tool {
+        "git" => &["help", "-a"],
+        "cargo" => &["--list"],
+        _ => &["--help"],
+    };
+    match output_with_timeout(Command::new(tool).args(args), HELP_TIMEOUT) {
+        Ok(output) => {
+            // Manche Werkzeuge schreiben ihre Hilfe nach stderr
+            let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            parse_help_commands(&text)
+        }
+        Err(e) => {
+            error!("Failed to list subcommands of {}: {}", tool, e);
+            Vec::new()
+        }
+    }
+}
+
+// Eingerückte Zeilen unter Überschriften wie "Common Commands:", "Unit Commands:" oder "Main Porcelain Commands"
+fn parse_help_commands(text: &str) -> Vec<(String, String)> {
+    let mut commands = Vec::new();
+    let mut in_commands = false;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_commands = line.to_lowercase().contains("command");
+            continue;
+        }
+        if !in_commands {
+            continue;
+        }
+        let trimmed = line.trim();
+        let (name, description) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+        let name = name.trim_end_matches([',', ':', '*']);
+        let is_name = name.len() > 1
+            && name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if is_name {
+            // Argumente wie "[PATTERN...]" stehen bei systemctl vor der Beschreibung
+            let description = description
+                .trim()
+                .rsplit("  ")
+                .map(str::trim)
+                .find(|part| !part.is_empty())
+                .unwrap_or_default();
+            commands.push((name.to_string(), description.to_string()));
+        }
+    }
+    commands
+}
+
+// git-rebase(1), sonst `git help rebase`, sonst der passende Abschnitt aus der Manpage des Werkzeugs (systemctl)
+pub fn documentation(parent: &str, sub: &str, description: Option<&str>) -> io::Result<String> {
+    if let Ok(manpage) = get_manpage(&format!("{}-{}", parent, sub)) {
+        if !manpage.is_empty() {
+            return Ok(manpage);
+        }
+    }
+    if HELP_SUBCOMMAND_TOOLS.contains(&parent) {
+        if let Ok(output) = output_with_timeout(Command::new(parent).args(["help", sub]), HELP_TIMEOUT) {
+            let help = String::from_utf8_lossy(&output.stdout).to_string();
+            if output.status.success() && !help.trim().is_empty() {
+                return Ok(help);
+            }
+        }
+    }
+    if let Some(entry) = get_manpage(parent).ok().and_then(|manpage| extract_entry(&manpage, sub)) {
+        return Ok(format!("{} {}\n\nCOMMAND\n{}\n", parent, sub, entry));
+    }
+    match description {
+        Some(description) => Ok(format!("{} {}\n\nDESCRIPTION\n       {}\n", parent, sub, description)),
+        None => Err(io::Error::other(format!("no documentation for {} {}", parent, sub))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(commands: &[(String, String)]) -> Vec<&str> {
+        commands.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn subcommands_from_man_page_file_names() {
+        let tools = ["git", "docker"];
+        let found: Vec<(String, String)> = ["git-rebase.1.gz", "git-lfs-push.1", "docker-run.1.zst", "git.1.gz", "gitk.1", "git-.1", "README"]
+            .iter()
+            .flat_map(|file_name| page_subcommands(file_name, &tools).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(found, [
+            ("git".to_string(), "rebase".to_string()),
+            ("git".to_string(), "lfs-push".to_string()),
+            ("docker".to_string(), "run".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn git_help_all() {
+        let text = concat!(
+            "See 'git help <command>' to read about a specific subcommand\n",
+            "\n",
+            "Main Porcelain Commands\n",
+            "   add                     Add file contents to the index\n",
+            "   rebase                  Reapply commits on top of another base tip\n",
+            "\n",
+            "Ancillary Commands / Manipulators\n",
+            "   config                  Get and set repository or global options\n",
+            "\n",
+            "External commands\n",
+            "   lfs\n",
+        );
+        let commands = parse_help_commands(text);
+        assert_eq!(names(&commands), ["add", "rebase", "config", "lfs"]);
+        assert_eq!(commands[1].1, "Reapply commits on top of another base tip");
+        assert_eq!(commands[3].1, "");
+    }
+
+    #[test]
+    fn docker_help_stops_at_option_headings() {
+        let text = concat!(
+            "\n",
+            "Usage:  docker [OPTIONS] COMMAND\n",
+            "\n",
+            "A self-sufficient runtime for containers\n",
+            "\n",
+            "Common Commands:\n",
+            "  run         Create and run a new container from an image\n",
+            "  ps          List containers\n",
+            "\n",
+            "Management Commands:\n",
+            "  compose*    Docker Compose (Docker Inc., v2.24.5)\n",
+            "\n",
+            "Global Options:\n",
+            "      --config string      Location of client config files\n",
+            "  -D, --debug              Enable debug mode\n",
+            "\n",
+            "Run 'docker COMMAND --help' for more information on a command.\n",
+        );
+        let commands = parse_help_commands(text);
+        assert_eq!(names(&commands), ["run", "ps", "compose"]);
+        assert_eq!(commands[2].1, "Docker Compose (Docker Inc., v2.24.5)");
+    }
+
+    #[test]
+    fn systemctl_help_splits_arguments_from_descriptions() {
+        let text = concat!(
+            "systemctl [OPTIONS...] COMMAND ...\n",
+            "\n",
+            "Query or send control commands to the system manager.\n",
+            "\n",
+            "Unit Commands:\n",
+            "  list-units [PATTERN...]             List units currently in memory\n",
+            "  start UNIT...                       Start (activate) one or more units\n",
+            "  is-active PATTERN...                Check whether units are active\n",
+            "\n",
+            "Options:\n",
+            "  -h --help              Show this help\n",
+        );
+        let commands = parse_help_commands(text);
+        assert_eq!(names(&commands), ["list-units", "start", "is-active"]);
+        assert_eq!(commands[0].1, "List units currently in memory");
+        assert_eq!(commands[1].1, "Start (activate) one or more units");
+        assert_eq!(commands[2].1, "Check whether units are active");
+    }
+}
@@ -0,0 +1,38 @@
+use crate::manpage::next_match;
+use serde::{Deserialize, Serialize};
+
+// Eine geöffnete Manpage. Der aktive Tab lebt in den manpage-Feldern von MyApp und wird erst beim Wechsel
+// hierher zurückgeschrieben; alle anderen Tabs, auch der in der geteilten Ansicht, halten ihren Zustand selbst.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ManpageTab {
+    pub command: String,
+    // Wird nach einem Neustart erst beim Anzeigen wieder geladen
+    #[serde(skip)]
+    pub manpage: String,
+    pub search_query: String,
+    pub scroll_offset: f32,
+    pub current_match: Option<usize>,
+    // Abschnitt (NAME, OPTIONS, …) an der Scrollposition
+    pub section: Option<String>,
+    // Überschriften mit ihrer Position aus dem letzten Frame, für die Sprungauswahl
+    #[serde(skip)]
+    pub sections: Vec<(String, f32)>,
+    #[serde(skip)]
+    pub pending_scroll_offset: Option<f32>,
+    #[serde(skip)]
+    pub scroll_to_match: bool,
+}
+
+impl ManpageTab {
+    pub fn new(command: &str) -> Self {
+        Self { command: command.to_string(), ..Self::default() }
+    }
+
+    pub fn jump_to_match(&mut self, direction: isize) {
+        if self.search_query.is_empty() {
+            return;
+        }
+        self.current_match = next_match(&self.manpage, &self.search_query, self.current_match, direction);
+        self.scroll_to_match = self.current_match.is_some();
+    }
+}
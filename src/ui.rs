@@ -1,81 +1,1839 @@
-use egui::{CentralPanel, ScrollArea, SidePanel, Align};
-use syntect::highlighting::{ThemeSet, Style, HighlightLines};
-use syntect::parsing::SyntaxSet;
-use syntect::util::LinesWithEndings;
-use crate::my_app::MyApp;
+use crate::app::{MyApp, Pane};
+use crate::command_list::{ListClick, RowStyle};
+use crate::completion::CompletionNode;
+use crate::examples::{placeholders, ExampleTarget, PlaceholderForm, EXAMPLE_TARGET_KEY};
+use crate::highlight::{Highlighter, THEME_KEY};
+use crate::sandbox::{destructive_programs, OutputStream, RUN_SETTINGS_KEY};
+use crate::keymap::{Action, KEYBINDINGS_FILE};
+use crate::package::format_date;
+use crate::categories::{Category, CategorySource};
+use crate::command_filter::{self, CommandKind, FilterRule, Pattern, RuleAction};
+use crate::inventory::CatalogDiff;
+use crate::whats_new::Change;
+use crate::version::{is_probe_allowed, parse_semver, CachedVersion};
+use crate::path_check;
+use crate::search_history::SuggestionKind;
+use crate::shell::{ShellKind, SHELL_KEY};
+use crate::{command_list, config, session};
+use eframe::egui;
+
+pub const COMMAND_SEARCH_ID: &str = "command_search";
+pub const MANPAGE_SEARCH_ID: &str = "manpage_search";
 
 impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Left panel for commands
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.poll_shell_discovery();
+        self.poll_documentation();
+        self.poll_explainer();
+        self.poll_subcommand_discovery();
+        self.poll_package_lookups();
+        self.poll_variant_lookups();
+        self.poll_version_probes();
+        self.poll_catalog_export();
+        self.poll_path_changes();
+        self.poll_category_discovery();
+        self.poll_whats_new_scan();
+        if self.background_work_pending() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
         egui::SidePanel::left("side_panel").show(ctx, |ui| {
-            ui.heading("Programme");
-
+            self.focus_pane_on_click(ui, Pane::CommandList);
             ui.horizontal(|ui| {
+                pane_heading(ui, "Programme:", self.focused_pane == Pane::CommandList);
+                ui.label(format!("{} Befehle", self.filtered_commands.len()));
+                let filter_hint = format!("{} Einträge durch Filterregeln ausgeblendet", self.filter_stats.hidden);
+                ui.toggle_value(&mut self.filter_open, "⛃ Filter").on_hover_text(filter_hint);
+            });
+            self.show_shell_selection(ui);
+            self.show_category_browser(ui);
+            let search_response = ui.horizontal(|ui| {
                 ui.label("Suche:");
-                if ui.text_edit_singleline(&mut self.search_query).changed() {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.search_query)
+                    .id(egui::Id::new(COMMAND_SEARCH_ID)));
+                if response.changed() {
                     self.filter_commands();
-                    self.add_to_search_history(self.search_query.clone());
+                }
+                response
+            }).inner;
+            self.show_search_suggestions(ui, &search_response);
+            let shell_entries = &self.shell_entries;
+            let subcommands = &self.subcommands.children;
+            let expanded_commands = &self.expanded_commands;
+            let command_changes = &self.command_changes;
+            let variants = &self.variants;
+            let clicked = command_list::show(
+                ui,
+                &self.filtered_commands,
+                self.selected_command.as_deref(),
+                self.scroll_to_selected,
+                |name| RowStyle {
+                    note: match (shell_entries.get(name), variants.children.get(name)) {
+                        (Some(entry), _) => Some(format!("{} {}", entry.shell.name(), entry.kind.label())),
+                        (None, Some(members)) => Some(format!("+{} Varianten", members.len())),
+                        (None, None) => None,
+                    },
+                    expanded: (subcommands.contains_key(name) || variants.children.contains_key(name))
+                        .then(|| expanded_commands.contains(name)),
+                    change: command_changes.get(name).map(|(change, _)| *change),
+                    nested: variants.parents.get(name).is_some_and(|parent| expanded_commands.contains(parent)),
+                },
+            );
+            self.scroll_to_selected = false;
+            match clicked {
+                Some(ListClick::Select(command)) => {
+                    self.select_command(&command);
+                    let query = self.search_query.clone();
+                    self.add_to_search_history(&query);
+                }
+                Some(ListClick::OpenInTab(command)) => self.open_in_new_tab(&command),
+                Some(ListClick::Toggle(command)) => self.toggle_subcommands(&command),
+                None => {}
+            }
+        });
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!self.back_stack.is_empty(), egui::Button::new("⬅"))
+                    .on_hover_text(format!("Zurück ({})", self.keymap.describe(Action::Back)))
+                    .clicked()
+                {
+                    self.go_back();
+                }
+                if ui.add_enabled(!self.forward_stack.is_empty(), egui::Button::new("➡"))
+                    .on_hover_text(format!("Vorwärts ({})", self.keymap.describe(Action::Forward)))
+                    .clicked()
+                {
+                    self.go_forward();
+                }
+                if let Some(command) = self.selected_command.clone() {
+                    let star = if self.is_favorite(&command) { "★" } else { "☆" };
+                    if ui.button(star)
+                        .on_hover_text(format!("Favorit ({})", self.keymap.describe(Action::ToggleFavorite)))
+                        .clicked()
+                    {
+                        self.toggle_favorite(&command);
+                    }
+                    if ui.button(format!("Example Usage for {}", command)).clicked() {
+                        self.open_perplexity_search();
+                    }
+                    if !self.options.is_empty() && ui.button("🛠 Befehl zusammenstellen").clicked() {
+                        self.open_command_builder();
+                    }
+                } else {
+                    ui.label("Select a command to see example usage");
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("🆕 Neu")
+                        .on_hover_text(format!("Neu auf diesem System ({})", self.keymap.describe(Action::WhatsNew)))
+                        .clicked()
+                    {
+                        self.toggle_whats_new();
+                    }
+                    if ui.button("🩺 PATH")
+                        .on_hover_text(format!("PATH-Verzeichnisse prüfen ({})", self.keymap.describe(Action::PathCheck)))
+                        .clicked()
+                    {
+                        self.toggle_path_check();
+                    }
+                    if ui.button("📋 Inventar")
+                        .on_hover_text(format!("Versionen der Werkzeuge exportieren ({})", self.keymap.describe(Action::Inventory)))
+                        .clicked()
+                    {
+                        self.inventory_open = !self.inventory_open;
+                    }
+                    if ui.button("▶ Ausprobieren")
+                        .on_hover_text(format!("Befehl in einer Sandbox ausführen ({})", self.keymap.describe(Action::RunCommand)))
+                        .clicked()
+                    {
+                        self.open_run_panel(None);
+                    }
+                    if ui.button("❓ Erklären")
+                        .on_hover_text(format!("Befehlszeile erklären ({})", self.keymap.describe(Action::ExplainCommand)))
+                        .clicked()
+                    {
+                        self.explain_open = !self.explain_open;
+                    }
+                    if ui.button("⌨").on_hover_text(self.keymap.describe(Action::ToggleHelp)).clicked() {
+                        self.help_open = !self.help_open;
+                    }
+                    let mut load_session = None;
+                    let mut delete_session = None;
+                    egui::ComboBox::from_id_source("session_combo")
+                        .selected_text("Sitzung laden")
+                        .show_ui(ui, |ui| {
+                            if self.sessions.is_empty() {
+                                ui.label("Keine gespeicherten Sitzungen");
+                            }
+                            for name in self.sessions.keys() {
+                                ui.horizontal(|ui| {
+                                    if ui.button("🗑").clicked() {
+                                        delete_session = Some(name.clone());
+                                    }
+                                    if ui.selectable_label(*name == self.session_name, name).clicked() {
+                                        load_session = Some(name.clone());
+                                    }
+                                });
+                            }
+                        });
+                    if ui.button("Sitzung speichern").clicked() {
+                        self.save_named_session(ctx);
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut self.session_name)
+                        .hint_text("Sitzungsname")
+                        .desired_width(120.0));
+                    if let Some(name) = load_session {
+                        self.load_named_session(&name, ctx);
+                    }
+                    if let Some(name) = delete_session {
+                        self.delete_named_session(&name);
+                    }
+                });
+            });
+        });
+
+        let history_height = ((self.command_history.len() as f32 / 10.0).ceil() * 30.0).max(100.0);
+
+        egui::TopBottomPanel::bottom("history_panel")
+            .resizable(false)
+            .min_height(history_height)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(ui.style().visuals.extreme_bg_color)
+                    .show(ui, |ui| {
+                        let mut selected_command = None;
+                        let mut selected_favorite = None;
+                        if !self.favorites.is_empty() {
+                            ui.add_space(10.0);
+                            ui.horizontal_wrapped(|ui| {
+                                ui.heading("Favoriten");
+                                for entry in &self.favorites {
+                                    let button = ui.button(format!("★ {}", entry));
+                                    if button.secondary_clicked() {
+                                        selected_favorite = Some((entry.clone(), true));
+                                    } else if button.on_hover_text("Rechtsklick entfernt den Favoriten").clicked() {
+                                        selected_favorite = Some((entry.clone(), false));
+                                    }
+                                }
+                            });
+                        }
+                        ui.add_space(10.0);
+                        ui.horizontal(|ui| {
+                            ui.heading("Historie");
+                            if ui.button("Löschen").clicked() {
+                                self.clear_history();
+                            }
+                        });
+                        ui.add_space(5.0);
+
+                        egui::Grid::new("history_grid")
+                            .num_columns(10)
+                            .spacing([5.0, 5.0])
+                            .show(ui, |ui| {
+                                for (index, command) in self.command_history.iter().enumerate() {
+                                    if ui.button(command).clicked() {
+                                        selected_command = Some(command.clone());
+                                    }
+                                    if (index + 1) % 10 == 0 {
+                                        ui.end_row();
+                                    }
+                                }
+                            });
+
+                        if let Some(command) = selected_command {
+                            self.set_search_query(String::new());
+                            self.select_command(&command);
+                        }
+                        match selected_favorite {
+                            Some((entry, true)) => self.toggle_favorite(&entry),
+                            Some((entry, false)) => self.open_favorite(&entry, ctx),
+                            None => {}
+                        }
+
+                        ui.add_space(10.0);
+                    });
+            });
+
+        self.show_completion_panel(ctx);
+        self.show_split_view(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.focus_pane_on_click(ui, Pane::Manpage);
+            self.show_tab_bar(ui);
+            ui.horizontal(|ui| {
+                pane_heading(ui, "Manpage", self.focused_pane == Pane::Manpage);
+                if let Some(command) = &self.selected_command {
+                    if self.shell_completions.contains(command) {
+                        ui.weak(format!("Vervollständigung in {} vorhanden", self.shell.name()));
+                    }
+                }
+                self.show_version(ui);
+                if self.completion_spec.is_some() {
+                    ui.toggle_value(&mut self.completion_open, "🌲 Unterbefehle & Optionen");
+                }
+            });
+            self.show_package_info(ui);
+            self.show_variant_info(ui);
+            ui.horizontal(|ui| {
+                if ui.button("⬆ Scroll to Top").clicked() {
+                    self.scroll_to_top = true;
+                    self.scroll_to_bottom = false;
+                }
+                if ui.button("⬇ Scroll to Bottom").clicked() {
+                    self.scroll_to_bottom = true;
+                    self.scroll_to_top = false;
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    egui::ComboBox::from_id_source("theme_combo")
+                        .selected_text(self.theme_name.as_str())
+                        .show_ui(ui, |ui| {
+                            for name in self.highlighter.theme_names() {
+                                ui.selectable_value(&mut self.theme_name, name.clone(), name);
+                            }
+                        });
+                    ui.label("Theme:");
+                });
+            });
+            ui.horizontal(|ui| {
+                ui.label("Suche in Manpage:");
+                let response = ui.add(egui::TextEdit::singleline(&mut self.manpage_search_query)
+                    .id(egui::Id::new(MANPAGE_SEARCH_ID)));
+                if response.changed() {
+                    self.current_match = None;
+                    self.scroll_to_top = true;
+                    self.scroll_to_bottom = false;
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.jump_to_match(1);
+                }
+                if let Some(index) = self.current_match {
+                    ui.label(format!("Treffer {}", index + 1));
+                }
+                if let Some(offset) = section_combo(ui, "manpage_section_combo", &self.manpage_sections, self.manpage_section.as_deref()) {
+                    self.pending_scroll_offset = Some(offset);
+                }
+            });
+            self.show_examples(ui);
+            let scroll = ManpageScroll {
+                offset: if std::mem::take(&mut self.scroll_to_top) { Some(0.0) } else { self.pending_scroll_offset.take() },
+                to_match: std::mem::take(&mut self.scroll_to_match),
+                to_bottom: std::mem::take(&mut self.scroll_to_bottom),
+            };
+            let text = ManpageText {
+                manpage: &self.manpage,
+                query: &self.manpage_search_query,
+                current_match: self.current_match,
+            };
+            let view = show_manpage_text(ui, "manpage_scroll_area", &mut self.highlighter, &self.theme_name, text, scroll);
+            self.manpage_scroll_offset = view.offset;
+            self.manpage_viewport_height = view.viewport_height;
+            self.manpage_line_height = ui.text_style_height(&egui::TextStyle::Body);
+            self.manpage_section = view.section;
+            self.manpage_sections = view.sections;
+        });
+
+        self.show_placeholder_form(ctx);
+        self.show_command_builder(ctx);
+        self.show_explain(ctx);
+        self.show_run_panel(ctx);
+        self.show_inventory(ctx);
+        self.show_path_check(ctx);
+        self.show_filter_rules(ctx);
+        self.show_whats_new(ctx);
+        self.show_command_palette(ctx);
+        self.show_help(ctx);
+        show_frame_time(ctx, frame);
+
+        for action in self.keymap.consume(ctx) {
+            self.run_action(action, ctx);
+        }
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.save_filter_rules();
+        session::save(storage, &self.current_session(None), &self.sessions);
+        eframe::set_value(storage, THEME_KEY, &self.theme_name);
+        eframe::set_value(storage, EXAMPLE_TARGET_KEY, &self.example_target);
+        eframe::set_value(storage, RUN_SETTINGS_KEY, &self.run_settings);
+        eframe::set_value(storage, SHELL_KEY, &self.shell_override);
+    }
+}
+
+fn show_completion_node(ui: &mut egui::Ui, node: &CompletionNode, path: &str, search_flag: &mut Option<String>) {
+    for option in &node.options {
+        ui.horizontal_wrapped(|ui| {
+            let mut flags = option.flags.join(", ");
+            if option.takes_argument {
+                flags.push_str(" …");
+            }
+            if ui.link(egui::RichText::new(flags).monospace()).clicked() {
+                *search_flag = option.flags.last().cloned();
+            }
+            if !option.description.is_empty() {
+                ui.weak(&option.description);
+            }
+        });
+    }
+    if !node.values.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Werte:");
+            ui.monospace(node.values.join(" "));
+        });
+    }
+    for child in &node.children {
+        let child_path = format!("{} {}", path, child.name);
+        if child.is_empty() {
+            let label = ui.monospace(&child.name);
+            if !child.description.is_empty() {
+                label.on_hover_text(&child.description);
+            }
+            continue;
+        }
+        let header = egui::CollapsingHeader::new(egui::RichText::new(&child.name).monospace())
+            .id_source(&child_path)
+            .show(ui, |ui| show_completion_node(ui, child, &child_path, search_flag));
+        if !child.description.is_empty() {
+            header.header_response.on_hover_text(&child.description);
+        }
+    }
+}
+
+// Nur in Debug-Builds: CPU-Zeit des letzten Frames unten rechts
+fn show_frame_time(ctx: &egui::Context, frame: &eframe::Frame) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let Some(cpu_usage) = frame.info().cpu_usage else {
+        return;
+    };
+    egui::Area::new(egui::Id::new("frame_time_overlay"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new(format!("Frame: {:.2} ms", cpu_usage * 1000.0))
+                .monospace()
+                .small()
+                .background_color(ui.visuals().extreme_bg_color));
+        });
+}
+
+struct ManpageText<'a> {
+    manpage: &'a str,
+    query: &'a str,
+    current_match: Option<usize>,
+}
+
+// Scroll-Wünsche für diesen Frame
+#[derive(Default)]
+struct ManpageScroll {
+    offset: Option<f32>,
+    to_match: bool,
+    to_bottom: bool,
+}
+
+struct ManpageView {
+    offset: f32,
+    viewport_height: f32,
+    section: Option<String>,
+    sections: Vec<(String, f32)>,
+}
+
+// Hervorgehobene Manpage in einer eigenen ScrollArea; gemeinsam für den aktiven Tab und die geteilte Ansicht
+fn show_manpage_text(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    highlighter: &mut Highlighter,
+    theme: &str,
+    text: ManpageText,
+    scroll: ManpageScroll,
+) -> ManpageView {
+    let mut scroll_area = egui::ScrollArea::vertical()
+        .id_source(id_source)
+        .auto_shrink([false; 2]);
+    if let Some(offset) = scroll.offset {
+        scroll_area = scroll_area.vertical_scroll_offset(offset);
+    }
+    let output = scroll_area.show(ui, |ui| {
+        if text.manpage.is_empty() {
+            ui.label("Nicht verfügbar");
+            return None;
+        }
+        let layout = highlighter.layout(ui, text.manpage, text.query, theme);
+        // Hinterlegung des aktuellen Treffers vor dem Text zeichnen, damit sie dahinter liegt
+        let match_rect = text
+            .current_match
+            .and_then(|index| layout.line_rect(index))
+            .map(|rect| rect.translate(ui.cursor().min.to_vec2()));
+        if let Some(rect) = match_rect {
+            ui.painter().rect_filled(rect, 0.0, ui.visuals().selection.bg_fill);
+        }
+        ui.label(layout.galley.clone());
+        if scroll.to_match {
+            if let Some(rect) = match_rect {
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+            }
+        }
+        ui.add_space(20.0); // Fügt 20px Padding am unteren Rand hinzu
+        if scroll.to_bottom {
+            ui.scroll_to_cursor(Some(egui::Align::BOTTOM));
+        }
+        Some(layout)
+    });
+    let offset = output.state.offset.y;
+    let line_height = ui.text_style_height(&egui::TextStyle::Body);
+    let (section, sections) = match output.inner {
+        Some(layout) => (layout.section_at(offset, line_height).map(str::to_string), layout.sections),
+        None => (None, Vec::new()),
+    };
+    ManpageView { offset, viewport_height: output.inner_rect.height(), section, sections }
+}
+
+// Auswahl der Überschriften; liefert die Scrollposition, zu der gesprungen werden soll
+fn section_combo(ui: &mut egui::Ui, id_source: &str, sections: &[(String, f32)], current: Option<&str>) -> Option<f32> {
+    let mut jump = None;
+    ui.add_enabled_ui(!sections.is_empty(), |ui| {
+        egui::ComboBox::from_id_source(id_source)
+            .selected_text(current.unwrap_or("Abschnitt"))
+            .show_ui(ui, |ui| {
+                for (title, offset) in sections {
+                    if ui.selectable_label(current == Some(title.as_str()), title).clicked() {
+                        jump = Some(*offset);
+                    }
                 }
             });
+    });
+    jump
+}
+
+fn pane_heading(ui: &mut egui::Ui, text: &str, focused: bool) {
+    let mut heading = egui::RichText::new(text).heading();
+    if focused {
+        heading = heading.color(ui.visuals().selection.stroke.color);
+    }
+    ui.label(heading);
+}
 
-            let scroll_area = egui::ScrollArea::vertical();
-            scroll_area.show(ui, |ui| {
-                for command in self.filtered_commands.clone() {
-                    let is_selected = self.selected_command.as_ref() == Some(&command);
-                    if ui.selectable_label(is_selected, &command).clicked() {
-                        self.selected_command = Some(command.clone());
-                        self.update_manpage(&command);
+impl MyApp {
+    // Ermittelte Version, sonst die des Pakets; das Programm wird nur über den Knopf gestartet
+    fn show_version(&mut self, ui: &mut egui::Ui) {
+        let Some(command) = self.selected_command.clone() else {
+            return;
+        };
+        let program = command.split(' ').next().unwrap_or(&command).to_string();
+        if self.pending_versions.contains(&program) {
+            ui.spinner();
+            return;
+        }
+        match self.versions.get(&program) {
+            Some(CachedVersion { version: Some(version), .. }) => {
+                let text = match &version.semver {
+                    Some(semver) => format!("v{}", semver),
+                    None => version.raw.clone(),
+                };
+                ui.strong(text).on_hover_text(format!("{}\n(ermittelt mit {} {})", version.raw, program, version.probe));
+            }
+            Some(CachedVersion { version: None, .. }) => {
+                ui.weak("Version unbekannt");
+            }
+            None => {
+                if let Some(package) = self.packages.get(&program).and_then(Option::as_ref) {
+                    if let Some(version) = &package.version {
+                        let text = parse_semver(version).map(|semver| format!("v{}", semver)).unwrap_or_else(|| version.clone());
+                        ui.label(text).on_hover_text(format!("Paketversion {} ({})", version, package.manager.label()));
                     }
-                    if is_selected && self.scroll_to_selected {
-                        ui.scroll_to_cursor(Some(Align::Center));
-                        self.scroll_to_selected = false;
+                }
+                let probeable = !self.shell_entries.contains_key(&program) && is_probe_allowed(&program);
+                if probeable && ui.small_button("Version ermitteln").on_hover_text(format!("Führt {} --version aus", program)).clicked() {
+                    self.request_version(&program);
+                }
+            }
+        }
+    }
+
+    fn show_inventory(&mut self, ctx: &egui::Context) {
+        if !self.inventory_open {
+            return;
+        }
+        let mut open = self.inventory_open;
+        egui::Window::new("Inventar").open(&mut open).default_width(520.0).show(ctx, |ui| {
+            ui.label(format!("{} Werkzeuge mit ermittelter Version", self.versions.values().filter(|cached| cached.version.is_some()).count()));
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                let mut commands: Vec<&String> = self.versions.keys().collect();
+                commands.sort();
+                egui::Grid::new("inventory_grid").striped(true).show(ui, |ui| {
+                    for command in commands {
+                        let cached = &self.versions[command];
+                        ui.monospace(command);
+                        match &cached.version {
+                            Some(version) => ui.label(version.semver.as_deref().unwrap_or(&version.raw)).on_hover_text(&version.raw),
+                            None => ui.weak("unbekannt"),
+                        };
+                        ui.weak(cached.path.display().to_string());
+                        ui.end_row();
                     }
+                });
+            });
+            ui.separator();
+            ui.strong("Katalog exportieren");
+            ui.weak("Alle Befehle mit Pfad, Paket, Version und Manpage; Format nach Dateiendung (.json oder .csv)");
+            ui.horizontal(|ui| {
+                ui.label("Datei:");
+                ui.text_edit_singleline(&mut self.inventory_path);
+                if self.catalog_build.is_some() {
+                    ui.spinner();
+                    let (done, total) = self.catalog_progress;
+                    ui.label(format!("Versionen {}/{}", done, total));
+                } else if ui.button("Exportieren").on_hover_text("Versionen aus dem Cache und den Paketen").clicked() {
+                    self.start_catalog_export(Vec::new());
                 }
             });
+            if self.catalog_build.is_none() {
+                match self.catalog_probe_confirmation.take() {
+                    None => {
+                        if ui.button("Mit Live-Abfrage der Versionen exportieren…").clicked() {
+                            self.prepare_version_probes();
+                        }
+                    }
+                    Some(candidates) => {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("{} Programme ohne bekannte Version werden mit --version gestartet.", candidates.len()),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Starten und exportieren").clicked() {
+                                self.start_catalog_export(candidates);
+                            } else if !ui.button("Abbrechen").clicked() {
+                                self.catalog_probe_confirmation = Some(candidates);
+                            }
+                        });
+                    }
+                }
+            }
+            ui.separator();
+            ui.strong("Zwei Exporte vergleichen");
+            egui::Grid::new("compare_grid").show(ui, |ui| {
+                ui.label("A:");
+                ui.text_edit_singleline(&mut self.compare_left);
+                ui.end_row();
+                ui.label("B:");
+                ui.text_edit_singleline(&mut self.compare_right);
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Vergleichen").clicked() {
+                    self.compare_catalogs();
+                }
+                if ui.button("Eigenen Export als A").clicked() {
+                    self.compare_left = self.inventory_path.clone();
+                }
+            });
+            if let Some(diff) = &self.catalog_diff {
+                show_catalog_diff(ui, diff);
+            }
+            if let Some(status) = &self.inventory_status {
+                ui.weak(status);
+            }
         });
+        self.inventory_open = open;
+    }
 
-        // Central panel for manpage
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Manpage");
+    fn show_path_check(&mut self, ctx: &egui::Context) {
+        if !self.path_check_open {
+            return;
+        }
+        let mut open = self.path_check_open;
+        let mut refresh = false;
+        let mut clicked_command = None;
+        egui::Window::new("PATH-Diagnose").open(&mut open).default_width(560.0).show(ctx, |ui| {
+            let Some(report) = &self.path_report else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{} Einträge, {} verdeckte Befehle, {} Probleme",
+                    report.dirs.len(),
+                    report.shadowed.len(),
+                    report.problem_count()
+                ));
+                if ui.button("🔄 Neu prüfen").clicked() {
+                    refresh = true;
+                }
+            });
+            ui.separator();
+            egui::Grid::new("path_dirs").striped(true).show(ui, |ui| {
+                ui.strong("#");
+                ui.strong("Verzeichnis");
+                ui.strong("Befehle");
+                ui.strong("Hinweise");
+                ui.end_row();
+                for (index, dir) in report.dirs.iter().enumerate() {
+                    ui.label((index + 1).to_string());
+                    ui.monospace(if dir.entry.is_empty() { "(leer = aktuelles Verzeichnis)" } else { &dir.entry });
+                    ui.label(dir.command_count.to_string());
+                    let mut problems = Vec::new();
+                    if !dir.exists {
+                        problems.push("existiert nicht");
+                    }
+                    if dir.world_writable {
+                        problems.push("für alle beschreibbar");
+                    }
+                    if dir.relative {
+                        problems.push("relativer Pfad");
+                    }
+                    if dir.duplicate {
+                        problems.push("doppelt");
+                    }
+                    if problems.is_empty() {
+                        ui.weak("–");
+                    } else {
+                        ui.colored_label(ui.visuals().error_fg_color, problems.join(", "));
+                    }
+                    ui.end_row();
+                }
+            });
+            egui::ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+                egui::CollapsingHeader::new(format!("Verdeckte Befehle ({})", report.shadowed.len())).show(ui, |ui| {
+                    ui.weak("Der erste Fund in PATH wird ausgeführt, die übrigen sind verdeckt.");
+                    for (command, paths) in &report.shadowed {
+                        ui.horizontal_wrapped(|ui| {
+                            if ui.link(egui::RichText::new(command).monospace()).clicked() {
+                                clicked_command = Some(command.clone());
+                            }
+                            for (index, path) in paths.iter().enumerate() {
+                                if index == 0 {
+                                    ui.strong(format!("✔ {}", path.display())).on_hover_text("Wird ausgeführt");
+                                } else {
+                                    ui.weak(path.display().to_string());
+                                }
+                            }
+                        });
+                    }
+                });
+                egui::CollapsingHeader::new(format!("Defekte Symlinks ({})", report.broken_symlinks.len())).show(ui, |ui| {
+                    for entry in &report.broken_symlinks {
+                        let target = entry.target.as_ref().map(|target| target.display().to_string()).unwrap_or_default();
+                        ui.monospace(format!("{} → {}", entry.path.display(), target));
+                    }
+                });
+                egui::CollapsingHeader::new(format!("Nicht ausführbar ({})", report.not_executable.len())).show(ui, |ui| {
+                    for entry in &report.not_executable {
+                        ui.monospace(entry.path.display().to_string());
+                    }
+                });
+            });
+        });
+        self.path_check_open = open;
+        if refresh {
+            self.path_report = Some(path_check::analyze());
+        }
+        if let Some(command) = clicked_command {
+            self.select_command(&command);
+        }
+    }
+
+    fn show_filter_rules(&mut self, ctx: &egui::Context) {
+        if !self.filter_open {
+            return;
+        }
+        let mut open = self.filter_open;
+        let mut rules = self.filter_rules.clone();
+        // Solange ein Muster bearbeitet wird, nur filtern und erst nach dem Verlassen des Felds speichern
+        let mut editing = false;
+        egui::Window::new("Filterregeln").open(&mut open).default_width(560.0).show(ctx, |ui| {
+            ui.label(format!(
+                "{} von {} Einträgen sichtbar, {} ausgeblendet",
+                self.commands.len(),
+                self.all_commands.len(),
+                self.filter_stats.hidden
+            ));
+            ui.weak("Gibt es Regeln „Nur zeigen“, muss ein Eintrag mindestens eine davon erfüllen. Jede Regel „Ausblenden“ entfernt ihre Treffer.");
+            ui.separator();
+            let mut remove = None;
+            egui::Grid::new("filter_rules").striped(true).show(ui, |ui| {
+                for (index, rule) in rules.iter_mut().enumerate() {
+                    ui.checkbox(&mut rule.enabled, "");
+                    egui::ComboBox::from_id_source(("filter_action", index))
+                        .selected_text(rule.action.label())
+                        .show_ui(ui, |ui| {
+                            for action in RuleAction::ALL {
+                                ui.selectable_value(&mut rule.action, action, action.label());
+                            }
+                        });
+                    let mut type_label = rule.pattern.type_label();
+                    egui::ComboBox::from_id_source(("filter_type", index))
+                        .selected_text(type_label)
+                        .show_ui(ui, |ui| {
+                            for label in Pattern::TYPE_LABELS {
+                                ui.selectable_value(&mut type_label, label, label);
+                            }
+                        });
+                    if type_label != rule.pattern.type_label() {
+                        rule.pattern = rule.pattern.with_type(type_label);
+                    }
+                    match &mut rule.pattern {
+                        Pattern::Glob(text) | Pattern::Regex(text) | Pattern::Directory(text) => {
+                            editing |= ui.add(egui::TextEdit::singleline(text).desired_width(180.0)).has_focus();
+                        }
+                        Pattern::Kind(kind) => {
+                            egui::ComboBox::from_id_source(("filter_kind", index))
+                                .selected_text(kind.label())
+                                .show_ui(ui, |ui| {
+                                    for option in CommandKind::ALL {
+                                        ui.selectable_value(kind, option, option.label());
+                                    }
+                                });
+                        }
+                    }
+                    match self.filter_stats.errors.get(index).and_then(Option::as_ref) {
+                        Some(error) => {
+                            ui.colored_label(ui.visuals().error_fg_color, "ungültig").on_hover_text(error);
+                        }
+                        None if rule.action == RuleAction::Exclude && rule.enabled => {
+                            let hidden = self.filter_stats.hidden_by_rule.get(index).copied().unwrap_or(0);
+                            ui.label(format!("blendet {} aus", hidden));
+                        }
+                        None => {
+                            ui.label("");
+                        }
+                    }
+                    if ui.small_button("🗑").on_hover_text("Regel entfernen").clicked() {
+                        remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(index) = remove {
+                rules.remove(index);
+            }
+            if rules.iter().any(|rule| rule.enabled && rule.action == RuleAction::Include) {
+                ui.label(format!("Von keiner Regel „Nur zeigen“ erfasst: {}", self.filter_stats.not_included));
+            }
+            ui.separator();
+            ui.horizontal_wrapped(|ui| {
+                if ui.button("➕ Regel").clicked() {
+                    rules.push(FilterRule {
+                        enabled: true,
+                        action: RuleAction::Exclude,
+                        pattern: Pattern::Glob(String::new()),
+                    });
+                }
+                for (name, preset) in command_filter::presets() {
+                    if ui.button(name).on_hover_text("Regeln der Vorlage hinzufügen").clicked() {
+                        rules.extend(preset);
+                    }
+                }
+                if ui.button("Standard wiederherstellen").clicked() {
+                    rules = command_filter::default_rules();
+                }
+            });
+        });
+        self.filter_open = open;
+        if rules != self.filter_rules {
+            self.set_filter_rules(rules);
+        }
+        if !editing || !open {
+            self.save_filter_rules();
+        }
+    }
 
-            let mut scroll_area = egui::ScrollArea::vertical().id_source("manpage_scroll_area");
+    fn show_variant_info(&mut self, ui: &mut egui::Ui) {
+        if self.variant_details.is_empty() {
+            return;
+        }
+        let mut clicked = None;
+        let title = format!("🔀 {} Varianten von {}", self.variant_details.len() - 1, self.variant_details[0].name);
+        egui::CollapsingHeader::new(title).id_source("variant_info").show(ui, |ui| {
+            egui::Grid::new("variant_grid").striped(true).show(ui, |ui| {
+                for variant in &self.variant_details {
+                    let selected = self.selected_command.as_deref() == Some(variant.name.as_str());
+                    if ui.selectable_label(selected, egui::RichText::new(&variant.name).monospace()).clicked() {
+                        clicked = Some(variant.name.clone());
+                    }
+                    if variant.is_default {
+                        ui.strong("Standard")
+                            .on_hover_text(format!("{} führt diese Datei aus", self.variant_details[0].name));
+                    } else {
+                        ui.label("");
+                    }
+                    let path = match (&variant.path, &variant.target) {
+                        (Some(path), Some(target)) => format!("{} → {}", path.display(), target.display()),
+                        (Some(path), None) => path.display().to_string(),
+                        (None, _) => "nicht in PATH".to_string(),
+                    };
+                    ui.weak(path);
+                    match &variant.alternative {
+                        Some(alternative) => {
+                            let choices: Vec<String> = variant.alternatives.iter().map(|path| path.display().to_string()).collect();
+                            ui.label(format!("update-alternatives: {}", alternative))
+                                .on_hover_text(format!("Zur Auswahl:\n{}", choices.join("\n")));
+                        }
+                        None => {
+                            ui.label("");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(command) = clicked {
+            self.select_command(&command);
+        }
+    }
 
-            if self.scroll_to_top {
-                scroll_area = scroll_area.vertical_scroll_offset(0.0);
-                self.scroll_to_top = false;
+    fn show_whats_new(&mut self, ctx: &egui::Context) {
+        if !self.whats_new_open {
+            return;
+        }
+        let mut open = self.whats_new_open;
+        let mut rescan = false;
+        let mut mark_seen = false;
+        let mut clicked_command = None;
+        egui::Window::new("Neu auf diesem System").open(&mut open).default_width(640.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                match &self.snapshot {
+                    Some(snapshot) => ui.label(format!("Verglichen mit dem Stand vom {}", format_date(snapshot.taken_at))),
+                    None => ui.label("Noch kein gespeicherter Stand"),
+                };
+                ui.separator();
+                ui.label("Zuletzt geändert in den letzten");
+                if ui.add(egui::DragValue::new(&mut self.whats_new_days).range(1..=365)).changed() {
+                    rescan = true;
+                }
+                ui.label("Tagen");
+            });
+            ui.horizontal(|ui| {
+                if self.whats_new_scan.is_some() {
+                    ui.spinner();
+                    ui.weak("Befehle werden geprüft …");
+                } else if ui.button("🔄 Neu prüfen").clicked() {
+                    rescan = true;
+                }
+                let has_changes = self.whats_new.iter().any(|entry| entry.change != Change::Recent);
+                if ui.add_enabled(has_changes, egui::Button::new("✔ Als gesehen markieren"))
+                    .on_hover_text("Den aktuellen Stand als neuen Vergleichspunkt speichern")
+                    .clicked()
+                {
+                    mark_seen = true;
+                }
+            });
+            ui.separator();
+            if self.whats_new.is_empty() && self.whats_new_scan.is_none() {
+                ui.weak("Nichts Neues gefunden.");
+                return;
             }
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("whats_new_grid").striped(true).show(ui, |ui| {
+                    ui.strong("Befehl");
+                    ui.strong("Änderung");
+                    ui.strong("Datei vom");
+                    ui.strong("Paket");
+                    ui.strong("Beschreibung");
+                    ui.end_row();
+                    for entry in &self.whats_new {
+                        let name = egui::RichText::new(&entry.command).monospace();
+                        if entry.change == Change::Removed {
+                            ui.label(name.strikethrough());
+                        } else if ui.link(name).clicked() {
+                            clicked_command = Some(entry.command.clone());
+                        }
+                        match entry.change {
+                            Change::Recent => ui.weak(entry.change.label()),
+                            Change::Removed => ui.colored_label(ui.visuals().error_fg_color, entry.change.label()),
+                            Change::Added | Change::Changed => ui.colored_label(ui.visuals().warn_fg_color, entry.change.label()),
+                        };
+                        let date = entry.mtime.map(format_date).unwrap_or_default();
+                        match &entry.path {
+                            Some(path) => ui.label(date).on_hover_text(path.display().to_string()),
+                            None => ui.label(date),
+                        };
+                        ui.label(entry.package.as_deref().unwrap_or("–"));
+                        ui.label(entry.description.as_deref().unwrap_or(""));
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        self.whats_new_open = open;
+        if mark_seen {
+            self.mark_whats_new_seen();
+        }
+        if rescan {
+            self.start_whats_new_scan();
+        }
+        if let Some(command) = clicked_command {
+            self.select_command(&command);
+        }
+    }
 
-            scroll_area.show(ui, |ui| {
-                if self.manpage.is_empty() {
-                    ui.label("Nicht verfügbar");
+    fn show_tab_bar(&mut self, ui: &mut egui::Ui) {
+        let mut activate = None;
+        let mut close = None;
+        let mut split = None;
+        let mut new_tab = false;
+        ui.horizontal_wrapped(|ui| {
+            let closable = self.manpage_tabs.len() > 1;
+            for (index, tab) in self.manpage_tabs.iter().enumerate() {
+                let command = if index == self.active_tab {
+                    self.selected_command.as_deref().unwrap_or("–")
                 } else {
-                    let filtered_manpage = self.filter_manpage();
-                    let syntax_set = SyntaxSet::load_defaults_newlines();
-                    let theme_set = ThemeSet::load_defaults();
-                    let syntax = syntax_set.find_syntax_plain_text();
-                    let mut h = HighlightLines::new(syntax, &theme_set.themes["base16-ocean.dark"]);
+                    tab.command.as_str()
+                };
+                let text = if self.split_tab == Some(index) { format!("◫ {}", command) } else { command.to_string() };
+                let response = ui
+                    .selectable_label(index == self.active_tab, text)
+                    .on_hover_text("Mittelklick schließt den Tab, Rechtsklick für weitere Aktionen");
+                if response.middle_clicked() {
+                    close = Some(index);
+                } else if response.clicked() {
+                    activate = Some(index);
+                }
+                response.context_menu(|ui| {
+                    if index != self.active_tab && ui.button("In geteilter Ansicht zeigen").clicked() {
+                        split = Some(index);
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(closable, egui::Button::new("Schließen")).clicked() {
+                        close = Some(index);
+                        ui.close_menu();
+                    }
+                });
+                if closable && ui.small_button("✕").clicked() {
+                    close = Some(index);
+                }
+                ui.separator();
+            }
+            if ui.small_button("+")
+                .on_hover_text(format!(
+                    "Manpage in neuem Tab öffnen ({}, Mittelklick oder Strg+Klick in der Liste)",
+                    self.keymap.describe(Action::NewTab)
+                ))
+                .clicked()
+            {
+                new_tab = true;
+            }
+            let mut split_open = self.split_tab.is_some();
+            if ui.toggle_value(&mut split_open, "◫ Geteilt")
+                .on_hover_text(format!("Zwei Manpages nebeneinander ({})", self.keymap.describe(Action::SplitView)))
+                .clicked()
+            {
+                self.toggle_split();
+            }
+        });
+        if let Some(index) = activate {
+            self.activate_tab(index);
+        }
+        if let Some(index) = split {
+            self.show_in_split(index);
+        }
+        if let Some(index) = close {
+            self.close_tab(index);
+        }
+        if new_tab {
+            if let Some(command) = self.selected_command.clone() {
+                self.open_in_new_tab(&command);
+            }
+        }
+    }
 
-                    let mut job = LayoutJob::default();
-                    for line in LinesWithEndings::from(&filtered_manpage) {
-                        let ranges: Vec<(Style, &str)> = h.highlight_line(line, &syntax_set).unwrap();
-                        for (style, text) in ranges {
-                            // Highlighting logic here
+    fn show_split_view(&mut self, ctx: &egui::Context) {
+        self.load_split_manpage();
+        let Some(index) = self.split_tab else {
+            return;
+        };
+        let mut swap = false;
+        let mut close = false;
+        egui::SidePanel::right("manpage_split_panel")
+            .resizable(true)
+            .default_width(ctx.screen_rect().width() / 3.0)
+            .show(ctx, |ui| {
+                let Some(tab) = self.manpage_tabs.get_mut(index) else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    pane_heading(ui, &tab.command, false);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").on_hover_text("Geteilte Ansicht schließen").clicked() {
+                            close = true;
                         }
+                        if ui.button("⇄").on_hover_text("Mit dem aktiven Tab tauschen").clicked() {
+                            swap = true;
+                        }
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Suche:");
+                    let response = ui.add(egui::TextEdit::singleline(&mut tab.search_query).desired_width(140.0));
+                    if response.changed() {
+                        tab.current_match = None;
+                        tab.pending_scroll_offset = Some(0.0);
+                    }
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        tab.jump_to_match(1);
+                    }
+                    if let Some(index) = tab.current_match {
+                        ui.label(format!("Treffer {}", index + 1));
+                    }
+                    if let Some(offset) = section_combo(ui, "split_section_combo", &tab.sections, tab.section.as_deref()) {
+                        tab.pending_scroll_offset = Some(offset);
+                    }
+                });
+                let scroll = ManpageScroll {
+                    offset: tab.pending_scroll_offset.take(),
+                    to_match: std::mem::take(&mut tab.scroll_to_match),
+                    ..ManpageScroll::default()
+                };
+                let text = ManpageText {
+                    manpage: &tab.manpage,
+                    query: &tab.search_query,
+                    current_match: tab.current_match,
+                };
+                let view = show_manpage_text(ui, "manpage_split_scroll_area", &mut self.highlighter, &self.theme_name, text, scroll);
+                tab.scroll_offset = view.offset;
+                tab.section = view.section;
+                tab.sections = view.sections;
+            });
+        if swap {
+            self.activate_tab(index);
+        }
+        if close {
+            self.split_tab = None;
+        }
+    }
+
+    fn show_package_info(&self, ui: &mut egui::Ui) {
+        let Some(command) = &self.selected_command else {
+            return;
+        };
+        let program = command.split(' ').next().unwrap_or(command);
+        ui.horizontal_wrapped(|ui| {
+            ui.label("📦");
+            match self.packages.get(program) {
+                Some(Some(package)) => {
+                    ui.strong(&package.name);
+                    if let Some(version) = &package.version {
+                        ui.label(version);
+                    }
+                    let mut source = package.manager.label().to_string();
+                    if let Some(installed_at) = package.installed_at {
+                        source.push_str(&format!(", installiert am {}", format_date(installed_at)));
                     }
+                    ui.weak(format!("({})", source));
+                    if let Some(summary) = &package.summary {
+                        ui.weak(format!("– {}", summary));
+                    }
+                }
+                Some(None) => {
+                    ui.weak("Kein Paket gefunden");
+                }
+                None => {
+                    ui.spinner();
+                    ui.weak("Paket wird ermittelt…");
+                }
+            }
+        });
+    }
+
+    // Baum aus der Vervollständigungsdatei neben der Manpage; ein Klick auf eine Option sucht sie in der Manpage
+    fn show_completion_panel(&mut self, ctx: &egui::Context) {
+        if !self.completion_open {
+            return;
+        }
+        let Some(spec) = &self.completion_spec else {
+            return;
+        };
+        let mut search_flag = None;
+        egui::SidePanel::right("completion_panel")
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.heading("Vervollständigung");
+                ui.weak(format!("{} ({})", spec.file.path.display(), spec.file.source.label()));
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    show_completion_node(ui, &spec.root, &spec.root.name, &mut search_flag);
+                });
+            });
+        if let Some(flag) = search_flag {
+            self.manpage_search_query = flag;
+            self.current_match = None;
+            self.jump_to_match(1);
+        }
+    }
+
+    // Gewählte Kategorien als Pfad ("Netzwerk › 8"), darunter die Kategorien, die die Auswahl weiter eingrenzen
+    fn show_category_browser(&mut self, ui: &mut egui::Ui) {
+        let mut toggled = None;
+        let title = match self.selected_categories.len() {
+            0 => "📂 Kategorien".to_string(),
+            count => format!("📂 Kategorien ({} gewählt)", count),
+        };
+        egui::CollapsingHeader::new(title).id_source("category_browser").show(ui, |ui| {
+            if self.category_discovery.is_some() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.weak("Kategorien werden ermittelt …");
+                });
+            }
+            if !self.selected_categories.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    for (index, category) in self.selected_categories.iter().enumerate() {
+                        if index > 0 {
+                            ui.label("›");
+                        }
+                        if ui.button(format!("{} ✖", category.label())).on_hover_text("Kategorie entfernen").clicked() {
+                            toggled = Some(category.clone());
+                        }
+                    }
+                });
+            }
+            egui::ScrollArea::vertical().id_source("category_list").max_height(220.0).show(ui, |ui| {
+                for source in CategorySource::ALL {
+                    let categories: Vec<&(Category, usize)> = self.category_counts
+                        .iter()
+                        .filter(|(category, _)| category.source == source)
+                        .collect();
+                    if categories.is_empty() {
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(format!("{} ({})", source.label(), categories.len()))
+                        .id_source(("category_source", source.label()))
+                        .show(ui, |ui| {
+                            for (category, count) in categories {
+                                if ui.selectable_label(false, format!("{}  {}", category.label(), count)).clicked() {
+                                    toggled = Some(category.clone());
+                                }
+                            }
+                        });
                 }
             });
         });
+        if let Some(category) = toggled {
+            self.toggle_category(category);
+        }
+    }
+
+    fn show_shell_selection(&mut self, ui: &mut egui::Ui) {
+        let mut shell_override = self.shell_override;
+        ui.horizontal(|ui| {
+            ui.label("Shell:");
+            let automatic = format!("Automatisch ({})", ShellKind::detect().name());
+            let selected_text = match shell_override {
+                Some(shell) => shell.name().to_string(),
+                None => automatic.clone(),
+            };
+            egui::ComboBox::from_id_source("shell_combo")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut shell_override, None, automatic);
+                    for shell in ShellKind::ALL {
+                        ui.add_enabled_ui(self.installed_shells.contains(&shell), |ui| {
+                            ui.selectable_value(&mut shell_override, Some(shell), shell.name())
+                                .on_disabled_hover_text("Nicht installiert");
+                        });
+                    }
+                });
+            if self.shell_discovery.is_some() {
+                ui.spinner().on_hover_text(format!("Lese Aliase und Funktionen aus {}…", self.shell.name()));
+            }
+        });
+        if shell_override != self.shell_override {
+            self.set_shell(shell_override);
+        }
+    }
 
-        // Right panel for search history
-        egui::SidePanel::right("history_panel").show(ctx, |ui| {
-            ui.heading("Suchhistorie");
-            for query in &self.search_history {
-                if ui.button(query).clicked() {
-                    self.search_query = query.clone();
-                    self.filter_commands();
-                    self.update_manpage(query);
+    fn show_search_suggestions(&mut self, ui: &mut egui::Ui, search_response: &egui::Response) {
+        let popup_id = ui.make_persistent_id("search_suggestions");
+        if search_response.gained_focus() || search_response.changed() {
+            self.search_suggestion_index = None;
+            ui.memory_mut(|m| m.open_popup(popup_id));
+        }
+
+        let popup_open = ui.memory(|m| m.is_popup_open(popup_id));
+        // Der Katalog wird nur durchsucht, solange das Dropdown offen ist
+        let suggestions = if popup_open { self.search_suggestions() } else { Vec::new() };
+        let selected = self.search_suggestion_index.and_then(|index| suggestions.get(index));
+
+        if popup_open && search_response.has_focus() && !suggestions.is_empty() {
+            // Die Pfeiltasten werden hier verbraucht, damit sie nicht gleichzeitig die Befehlsliste bewegen
+            let (down, up, delete) = ui.input_mut(|i| (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::SHIFT, egui::Key::Delete),
+            ));
+            if down {
+                self.search_suggestion_index = Some(match self.search_suggestion_index {
+                    Some(index) => (index + 1).min(suggestions.len() - 1),
+                    None => 0,
+                });
+            }
+            if up {
+                self.search_suggestion_index = match self.search_suggestion_index {
+                    Some(0) | None => None,
+                    Some(index) => Some(index - 1),
+                };
+            }
+            if delete {
+                if let Some(suggestion) = selected.filter(|s| s.kind == SuggestionKind::History) {
+                    let text = suggestion.text.clone();
+                    self.remove_from_search_history(&text);
+                    self.search_suggestion_index = None;
+                }
+            }
+        }
+
+        if search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            match selected.filter(|_| popup_open) {
+                Some(suggestion) => {
+                    let text = suggestion.text.clone();
+                    self.apply_search_suggestion(&text);
+                }
+                None => {
+                    let query = self.search_query.clone();
+                    self.add_to_search_history(&query);
+                }
+            }
+            ui.memory_mut(|m| m.close_popup());
+            return;
+        }
+
+        if suggestions.is_empty() {
+            return;
+        }
+
+        let mut chosen = None;
+        let mut removed = None;
+        let mut clear = false;
+        egui::popup_below_widget(ui, popup_id, search_response, egui::PopupCloseBehavior::CloseOnClickOutside, |ui| {
+            ui.set_min_width(search_response.rect.width());
+            for (index, suggestion) in suggestions.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let icon = match suggestion.kind {
+                        SuggestionKind::History => "🕘",
+                        SuggestionKind::Command => "▶",
+                    };
+                    ui.weak(icon);
+                    let highlighted = self.search_suggestion_index == Some(index);
+                    let label = ui.selectable_label(highlighted, &suggestion.text);
+                    if highlighted {
+                        label.scroll_to_me(None);
+                    }
+                    if label.clicked() {
+                        chosen = Some(suggestion.text.clone());
+                    }
+                    if suggestion.kind == SuggestionKind::History
+                        && ui.small_button("✖").on_hover_text("Aus der Suchhistorie entfernen (Shift+Entf)").clicked()
+                    {
+                        removed = Some(suggestion.text.clone());
+                    }
+                });
+            }
+            if suggestions.iter().any(|s| s.kind == SuggestionKind::History) {
+                ui.separator();
+                if ui.small_button("Suchhistorie löschen").clicked() {
+                    clear = true;
                 }
             }
         });
+
+        if let Some(query) = chosen {
+            self.apply_search_suggestion(&query);
+            ui.memory_mut(|m| m.close_popup());
+        }
+        if let Some(query) = removed {
+            self.remove_from_search_history(&query);
+            self.search_suggestion_index = None;
+        }
+        if clear {
+            self.clear_search_history();
+            self.search_suggestion_index = None;
+        }
     }
-}
\ No newline at end of file
+
+    fn focus_pane_on_click(&mut self, ui: &egui::Ui, pane: Pane) {
+        if ui.rect_contains_pointer(ui.max_rect()) && ui.input(|i| i.pointer.any_pressed()) {
+            self.focused_pane = pane;
+        }
+    }
+
+    fn show_command_palette(&mut self, ctx: &egui::Context) {
+        if !self.command_palette_open {
+            return;
+        }
+        let query = self.command_palette_query.to_lowercase();
+        let actions: Vec<Action> = Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::CommandPalette)
+            .filter(|action| action.label().to_lowercase().contains(&query))
+            .collect();
+        self.command_palette_index = self.command_palette_index.min(actions.len().saturating_sub(1));
+
+        let (down, up, enter, escape) = ctx.input_mut(|i| (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+        ));
+        if down && self.command_palette_index + 1 < actions.len() {
+            self.command_palette_index += 1;
+        }
+        if up {
+            self.command_palette_index = self.command_palette_index.saturating_sub(1);
+        }
+
+        let mut chosen = None;
+        if enter {
+            chosen = actions.get(self.command_palette_index).copied();
+        }
+        egui::Window::new("Befehlspalette")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.command_palette_query)
+                    .hint_text("Aktion suchen …")
+                    .desired_width(320.0));
+                response.request_focus();
+                if response.changed() {
+                    self.command_palette_index = 0;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (index, action) in actions.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            let highlighted = index == self.command_palette_index;
+                            let label = ui.selectable_label(highlighted, action.label());
+                            if highlighted {
+                                label.scroll_to_me(None);
+                            }
+                            if label.clicked() {
+                                chosen = Some(*action);
+                            }
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.weak(self.keymap.describe(*action));
+                            });
+                        });
+                    }
+                });
+            });
+
+        if escape || chosen.is_some() {
+            self.command_palette_open = false;
+            ctx.memory_mut(|m| m.stop_text_input());
+        }
+        if let Some(action) = chosen {
+            self.run_action(action, ctx);
+        }
+    }
+
+    fn show_help(&mut self, ctx: &egui::Context) {
+        if !self.help_open {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Tastenkürzel")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Grid::new("keybindings_grid").striped(true).show(ui, |ui| {
+                    for action in Action::ALL {
+                        ui.label(action.label());
+                        ui.monospace(self.keymap.describe(action));
+                        ui.end_row();
+                    }
+                });
+                if let Some(path) = config::config_path(KEYBINDINGS_FILE) {
+                    ui.separator();
+                    ui.weak(format!("Anpassbar in {}", path.display()));
+                }
+            });
+        if !open || ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.help_open = false;
+        }
+    }
+
+    fn show_examples(&mut self, ui: &mut egui::Ui) {
+        if self.examples.is_empty() {
+            return;
+        }
+        let mut copy = None;
+        let mut fill = None;
+        let mut run = None;
+        egui::CollapsingHeader::new(format!("Beispiele ({})", self.examples.len()))
+            .id_source("examples_header")
+            .open(std::mem::take(&mut self.expand_examples).then_some(true))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Kopieren nach:");
+                    let is_pipe = matches!(self.example_target, ExampleTarget::NamedPipe(_));
+                    ui.radio_value(&mut self.example_target, ExampleTarget::Clipboard, "Zwischenablage");
+                    ui.radio_value(&mut self.example_target, ExampleTarget::TmuxBuffer, "+ tmux-Puffer");
+                    if ui.radio(is_pipe, "+ Named Pipe").clicked() && !is_pipe {
+                        self.example_target = ExampleTarget::NamedPipe(String::new());
+                    }
+                    if let ExampleTarget::NamedPipe(path) = &mut self.example_target {
+                        ui.add(egui::TextEdit::singleline(path).hint_text("/tmp/cli_org.fifo").desired_width(160.0));
+                    }
+                });
+                egui::ScrollArea::vertical().id_source("examples_scroll").max_height(160.0).show(ui, |ui| {
+                    for example in &self.examples {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("📋").on_hover_text("Kopieren").clicked() {
+                                copy = Some(example.clone());
+                            }
+                            if !placeholders(example).is_empty()
+                                && ui.small_button("✏").on_hover_text("Platzhalter ausfüllen und kopieren").clicked()
+                            {
+                                fill = Some(example.clone());
+                            }
+                            if self.run_settings.enabled && ui.small_button("▶").on_hover_text("Im Ausführen-Panel öffnen").clicked() {
+                                run = Some(example.clone());
+                            }
+                            ui.monospace(example);
+                        });
+                    }
+                });
+            });
+        if let Some(example) = copy {
+            self.copy_example(ui.ctx(), &example);
+        }
+        if let Some(example) = fill {
+            self.placeholder_form = Some(PlaceholderForm::new(&example));
+        }
+        if let Some(example) = run {
+            self.open_run_panel(Some(&example));
+        }
+    }
+
+    fn show_placeholder_form(&mut self, ctx: &egui::Context) {
+        let Some(form) = &mut self.placeholder_form else {
+            return;
+        };
+        let mut open = true;
+        let mut copy = None;
+        egui::Window::new("Platzhalter ausfüllen")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.monospace(&form.command);
+                ui.separator();
+                egui::Grid::new("placeholder_grid").num_columns(2).show(ui, |ui| {
+                    for (index, (name, value)) in form.values.iter_mut().enumerate() {
+                        ui.label(name.as_str());
+                        let response = ui.text_edit_singleline(value);
+                        if index == 0 && ui.memory(|m| m.focused().is_none()) {
+                            response.request_focus();
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                let filled = form.filled();
+                ui.monospace(&filled);
+                if ui.button("📋 Kopieren").clicked() {
+                    copy = Some(filled);
+                }
+            });
+        if let Some(command) = copy {
+            self.copy_example(ctx, &command);
+            self.placeholder_form = None;
+        } else if !open {
+            self.placeholder_form = None;
+        }
+    }
+
+    fn show_command_builder(&mut self, ctx: &egui::Context) {
+        let Some(builder) = &mut self.command_builder else {
+            return;
+        };
+        let mut open = true;
+        let mut copy = None;
+        let mut save = None;
+        egui::Window::new(format!("Befehl zusammenstellen: {}", builder.command))
+            .id(egui::Id::new("command_builder"))
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Optionen filtern:");
+                    ui.text_edit_singleline(&mut builder.filter);
+                });
+                let filter = builder.filter.to_lowercase();
+                egui::ScrollArea::vertical().id_source("builder_options").max_height(260.0).show(ui, |ui| {
+                    for (index, option) in builder.options.iter().enumerate() {
+                        let visible = filter.is_empty()
+                            || option.display_flags().to_lowercase().contains(&filter)
+                            || option.description.to_lowercase().contains(&filter);
+                        if !visible {
+                            continue;
+                        }
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut builder.selected[index], egui::RichText::new(option.display_flags()).monospace())
+                                .on_hover_text(&option.description);
+                            if let (Some(argument), true) = (&option.argument, builder.selected[index]) {
+                                ui.add(egui::TextEdit::singleline(&mut builder.values[index])
+                                    .hint_text(argument.as_str())
+                                    .desired_width(140.0));
+                            }
+                        });
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Argumente:");
+                    if ui.small_button("➕").clicked() {
+                        builder.positionals.push(("ARG".to_string(), String::new()));
+                    }
+                });
+                let mut remove = None;
+                egui::Grid::new("builder_positionals").num_columns(3).show(ui, |ui| {
+                    for (index, (name, value)) in builder.positionals.iter_mut().enumerate() {
+                        ui.label(name.as_str());
+                        ui.text_edit_singleline(value);
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(index) = remove {
+                    builder.positionals.remove(index);
+                }
+
+                ui.separator();
+                let preview = builder.preview();
+                ui.add(egui::Label::new(egui::RichText::new(&preview).monospace().strong()).wrap());
+                for (option, _) in builder.selected_options() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.monospace(option.display_flags());
+                        ui.weak(&option.description);
+                    });
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("📋 Kopieren").clicked() {
+                        copy = Some(preview.clone());
+                    }
+                    if ui.button("★ In Favoriten speichern").clicked() {
+                        save = Some(preview.clone());
+                    }
+                });
+            });
+        if let Some(command_line) = copy {
+            self.copy_example(ctx, &command_line);
+        }
+        if let Some(command_line) = save {
+            self.save_built_command(&command_line);
+        }
+        if !open {
+            self.command_builder = None;
+        }
+    }
+
+    fn show_explain(&mut self, ctx: &egui::Context) {
+        if !self.explain_open {
+            return;
+        }
+        let mut open = true;
+        let mut explain = false;
+        let mut open_program = None;
+        egui::Window::new("Befehlszeile erklären")
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.explain_input)
+                        .hint_text("z. B. tar -xzvf foo.tgz -C /tmp | less")
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(460.0));
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        explain = true;
+                    }
+                    if ui.button("Erklären").clicked() {
+                        explain = true;
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().id_source("explain_scroll").max_height(400.0).show(ui, |ui| {
+                    for (index, command) in self.explain_result.iter().enumerate() {
+                        if !command.program.is_empty()
+                            && command.has_manpage
+                            && ui.link(egui::RichText::new(&command.program).strong()).clicked()
+                        {
+                            open_program = Some(command.program.clone());
+                        }
+                        egui::Grid::new(("explain_grid", index)).num_columns(2).striped(true).show(ui, |ui| {
+                            for explanation in &command.explanations {
+                                let token = egui::RichText::new(&explanation.token).monospace();
+                                if explanation.known {
+                                    ui.label(token.strong());
+                                } else {
+                                    ui.label(token.color(ui.visuals().warn_fg_color));
+                                }
+                                ui.add(egui::Label::new(&explanation.text).wrap());
+                                ui.end_row();
+                            }
+                        });
+                        ui.add_space(8.0);
+                    }
+                });
+            });
+        if explain {
+            self.explain_command_line();
+        }
+        if let Some(program) = open_program {
+            self.set_search_query(String::new());
+            self.select_command(&program);
+        }
+        if !open {
+            self.explain_open = false;
+        }
+    }
+
+    fn show_run_panel(&mut self, ctx: &egui::Context) {
+        if self.poll_run() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        if !self.run_open {
+            return;
+        }
+        let mut open = true;
+        let mut run = None;
+        let mut stop = false;
+        egui::Window::new("Befehl ausprobieren")
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.run_settings.enabled, "Ausführen erlauben");
+                if !self.run_settings.enabled {
+                    ui.weak("Befehle laufen in einem leeren Temp-Verzeichnis mit Zeitlimit, begrenzter Ausgabe und \
+                             reduzierter Umgebung. Trotzdem können sie auf das System zugreifen.");
+                    return;
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Zeitlimit (s):");
+                    ui.add(egui::DragValue::new(&mut self.run_settings.timeout_secs).range(1..=600));
+                    ui.label("Ausgabelimit (KB):");
+                    ui.add(egui::DragValue::new(&mut self.run_settings.output_limit_kb).range(1..=10_240));
+                });
+                let running = self.running_command.is_some();
+                ui.horizontal(|ui| {
+                    let response = ui.add_enabled(!running, egui::TextEdit::singleline(&mut self.run_input)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(460.0));
+                    if response.changed() {
+                        self.run_needs_confirmation = false;
+                    }
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        run = Some(false);
+                    }
+                    if running {
+                        if ui.button("⏹ Stopp").clicked() {
+                            stop = true;
+                        }
+                    } else if ui.button("▶ Ausführen").clicked() {
+                        run = Some(false);
+                    }
+                });
+                if self.run_needs_confirmation {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!("⚠ Potenziell zerstörerisch: {}", destructive_programs(&self.run_input).join(", ")),
+                        );
+                        if ui.button("Trotzdem ausführen").clicked() {
+                            run = Some(true);
+                        }
+                    });
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_source("run_output")
+                    .max_height(280.0)
+                    .auto_shrink([false, true])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for (stream, line) in &self.run_output {
+                            let text = egui::RichText::new(line).monospace();
+                            match stream {
+                                OutputStream::Stdout => ui.label(text),
+                                OutputStream::Stderr => ui.label(text.color(ui.visuals().error_fg_color)),
+                                OutputStream::Info => ui.label(text.italics().weak()),
+                            };
+                        }
+                    });
+
+                let program = self.selected_command.clone().unwrap_or_default();
+                if let Some(records) = self.run_log.get(&program).filter(|records| !records.is_empty()) {
+                    egui::CollapsingHeader::new(format!("Verlauf für {} ({})", program, records.len()))
+                        .id_source("run_log")
+                        .show(ui, |ui| {
+                            for record in records.iter().rev() {
+                                let status = match (record.exit_code, record.timed_out) {
+                                    (_, true) => "⏱".to_string(),
+                                    (Some(code), _) => code.to_string(),
+                                    (None, _) => "?".to_string(),
+                                };
+                                ui.horizontal(|ui| {
+                                    ui.weak(status);
+                                    if ui.link(egui::RichText::new(&record.command_line).monospace())
+                                        .on_hover_text(&record.output)
+                                        .clicked()
+                                    {
+                                        self.run_input = record.command_line.clone();
+                                    }
+                                });
+                            }
+                        });
+                }
+            });
+        if stop {
+            self.stop_run();
+        }
+        if let Some(confirmed) = run {
+            self.start_run(confirmed);
+        }
+        if !open {
+            self.run_open = false;
+        }
+    }
+}
+
+fn show_catalog_diff(ui: &mut egui::Ui, diff: &CatalogDiff) {
+    if diff.is_empty() {
+        ui.label(format!("Keine Unterschiede zwischen {} und {}", diff.left_host, diff.right_host));
+        return;
+    }
+    egui::ScrollArea::vertical().id_source("catalog_diff").max_height(300.0).show(ui, |ui| {
+        for (title, id, commands) in [
+            (format!("Nur auf {} ({})", diff.left_host, diff.only_left.len()), "diff_only_left", &diff.only_left),
+            (format!("Nur auf {} ({})", diff.right_host, diff.only_right.len()), "diff_only_right", &diff.only_right),
+        ] {
+            egui::CollapsingHeader::new(title).id_source(id).show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for command in commands {
+                        ui.monospace(command);
+                    }
+                });
+            });
+        }
+        for (title, id, rows) in [
+            (format!("Andere Version ({})", diff.version_mismatches.len()), "diff_versions", &diff.version_mismatches),
+            (format!("Anderer Pfad ({})", diff.path_differences.len()), "diff_paths", &diff.path_differences),
+        ] {
+            egui::CollapsingHeader::new(title).id_source(id).show(ui, |ui| {
+                egui::Grid::new(id).striped(true).show(ui, |ui| {
+                    ui.strong("Befehl");
+                    ui.strong(&diff.left_host);
+                    ui.strong(&diff.right_host);
+                    ui.end_row();
+                    for (command, left, right) in rows {
+                        ui.monospace(command);
+                        ui.label(left);
+                        ui.label(right);
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+    });
+}
@@ -0,0 +1,230 @@
+use crate::cli::command_path;
+use crate::process::output_with_timeout;
+use log::debug;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const ALTERNATIVES_DIR: &str = "/etc/alternatives";
+const ALTERNATIVES_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Name in /etc/alternatives -> Dateiname des aktuellen Ziels
+pub type AlternativeLinks = Vec<(String, String)>;
+
+// Varianten wie python3.11, python3-config oder x86_64-linux-gnu-gcc-12 unter einem gemeinsamen Eintrag
+#[derive(Default)]
+pub struct VariantGroups {
+    // Elterneintrag -> Varianten ohne ihn selbst, alphabetisch
+    pub children: HashMap<String, Vec<String>>,
+    // Variante -> Elterneintrag
+    pub parents: HashMap<String, String>,
+}
+
+// "x86_64-linux-gnu-gcc-12" -> "gcc-12"
+fn target_triplet() -> &'static Regex {
+    static TRIPLET: OnceLock<Regex> = OnceLock::new();
+    TRIPLET.get_or_init(|| {
+        Regex::new(r"^(x86_64|i[3-6]86|aarch64|arm\w*|riscv64|powerpc64le|ppc64le|s390x|mips\w*)-(\w+-)?(\w+-)?(gnu\w*|musl\w*|mingw32|darwin[\w.]*|elf)-")
+            .expect("valid triplet regex")
+    })
+}
+
+// Gemeinsamer Stamm: Zielplattform, "-config" und Versionsnummer am Ende entfernt.
+// "python3.11-config" -> "python", "gcc-12" -> "gcc"; Namen ohne solche Zusätze bleiben unverändert.
+pub fn base_name(command: &str) -> String {
+    let name = target_triplet().replace(command, "");
+    let name = name.strip_suffix("-config").unwrap_or(&name);
+    let without_version = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    if without_version.len() == name.len() {
+        return name.to_string();
+    }
+    let version = &name[without_version.len()..];
+    let base = without_version.trim_end_matches(['-', '_']);
+    // Direkt angehängt gilt nur eine Punktversion oder eine einzelne Ziffer an einem längeren Namen als Version
+    // (python3.11, python3, gpg2). base64, base32, x264, md5 und lz4 sind eigene Programme, keine Varianten.
+    let separated = base.len() < without_version.len();
+    let is_version = separated || version.contains('.') || (version.len() == 1 && base.len() >= 3);
+    // "7z" wäre sonst ""
+    if !is_version || base.len() < 2 {
+        return name.to_string();
+    }
+    base.to_string()
+}
+
+// `alternative_links` stammt aus `alternative_links()` und wird nur beim Einlesen der Befehle neu bestimmt
+pub fn group(commands: &[String], alternative_links: &[(String, String)]) -> VariantGroups {
+    let mut by_base: HashMap<String, Vec<String>> = HashMap::new();
+    for command in commands {
+        by_base.entry(base_name(command)).or_default().push(command.clone());
+    }
+    // Über update-alternatives verlinkte Namen gehören zur Gruppe ihres Ziels: cc -> gcc
+    let known: HashSet<&String> = commands.iter().collect();
+    for (name, target) in alternative_links {
+        if !known.contains(name) {
+            continue;
+        }
+        let target_base = base_name(target);
+        let own_base = base_name(name);
+        if target_base == own_base || !by_base.contains_key(&target_base) {
+            continue;
+        }
+        if let Some(members) = by_base.get_mut(&own_base) {
+            members.retain(|member| member != name);
+        }
+        by_base.entry(target_base).or_default().push(name.clone());
+    }
+
+    let mut groups = VariantGroups::default();
+    for (base, mut members) in by_base {
+        if members.len() < 2 {
+            continue;
+        }
+        members.sort();
+        let parent = members
+            .iter()
+            .find(|member| **member == base)
+            .or_else(|| members.iter().min_by_key(|member| (member.len(), member.as_str())))
+            .cloned()
+            .unwrap_or(base);
+        members.retain(|member| *member != parent);
+        for member in &members {
+            groups.parents.insert(member.clone(), parent.clone());
+        }
+        groups.children.insert(parent, members);
+    }
+    debug!("Grouped {} command variants under {} entries", groups.parents.len(), groups.children.len());
+    groups
+}
+
+pub fn alternative_links() -> AlternativeLinks {
+    let Ok(entries) = fs::read_dir(ALTERNATIVES_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let target = fs::canonicalize(entry.path()).ok()?;
+            let target_name = target.file_name()?.to_string_lossy().to_string();
+            Some((entry.file_name().to_string_lossy().to_string(), target_name))
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct Variant {
+    pub name: String,
+    pub path: Option<PathBuf>,
+    // Endgültiges Ziel, falls `path` ein Symlink ist
+    pub target: Option<PathBuf>,
+    // Name der Alternative, wenn der Symlink über /etc/alternatives führt
+    pub alternative: Option<String>,
+    // Alle Möglichkeiten, die update-alternatives für diesen Namen kennt
+    pub alternatives: Vec<PathBuf>,
+    // Die eigentliche Datei, die beim Aufruf des Elterneintrags ausgeführt wird (kein Symlink darauf)
+    pub is_default: bool,
+}
+
+// Startet update-alternatives für jede verlinkte Variante und gehört deshalb in einen Hintergrundthread
+pub fn details(parent: &str, members: &[String]) -> Vec<Variant> {
+    let parent_target = command_path(parent).and_then(|path| fs::canonicalize(path).ok());
+    std::iter::once(parent)
+        .chain(members.iter().map(String::as_str))
+        .map(|name| {
+            let path = command_path(name);
+            let resolved = path.as_ref().and_then(|path| fs::canonicalize(path).ok());
+            let alternative = path.as_deref().and_then(alternative_name);
+            let alternatives = alternative.as_deref().map(list_alternatives).unwrap_or_default();
+            Variant {
+                name: name.to_string(),
+                is_default: resolved.is_some() && resolved == parent_target && resolved == path,
+                target: resolved.filter(|resolved| Some(resolved) != path.as_ref()),
+                path,
+                alternative,
+                alternatives,
+            }
+        })
+        .collect()
+}
+
+// Folgt der Symlink-Kette, bis sie durch /etc/alternatives führt
+fn alternative_name(path: &Path) -> Option<String> {
+    let mut current = path.to_path_buf();
+    for _ in 0..8 {
+        let link = fs::read_link(&current).ok()?;
+        let next = match current.parent() {
+            Some(dir) if link.is_relative() => dir.join(link),
+            _ => link,
+        };
+        if next.parent() == Some(Path::new(ALTERNATIVES_DIR)) {
+            return next.file_name().map(|name| name.to_string_lossy().to_string());
+        }
+        current = next;
+    }
+    None
+}
+
+fn list_alternatives(name: &str) -> Vec<PathBuf> {
+    match output_with_timeout(Command::new("update-alternatives").args(["--list", name]), ALTERNATIVES_TIMEOUT) {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| PathBuf::from(line.trim()))
+            .collect(),
+        Err(e) => {
+            debug!("update-alternatives --list {} failed: {}", name, e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_name_strips_triplet_config_and_version() {
+        assert_eq!(base_name("python3.11-config"), "python");
+        assert_eq!(base_name("python3"), "python");
+        assert_eq!(base_name("x86_64-linux-gnu-gcc-12"), "gcc");
+        assert_eq!(base_name("aarch64-linux-gnu-ld"), "ld");
+        assert_eq!(base_name("gcc-12"), "gcc");
+        assert_eq!(base_name("7z"), "7z");
+        assert_eq!(base_name("x264"), "x264");
+        assert_eq!(base_name("git"), "git");
+        assert_eq!(base_name("gpg2"), "gpg");
+        assert_eq!(base_name("go1.22"), "go");
+    }
+
+    #[test]
+    fn base_name_keeps_numbers_that_belong_to_the_name() {
+        // Sonst fielen base64 und base32 in eine gemeinsame Gruppe "base"
+        assert_eq!(base_name("base64"), "base64");
+        assert_eq!(base_name("base32"), "base32");
+        assert_eq!(base_name("md5"), "md5");
+        assert_eq!(base_name("lz4"), "lz4");
+        let groups = group(&["base32", "base64", "md5", "md5sum"].map(String::from), &[]);
+        assert!(groups.children.is_empty());
+    }
+
+    #[test]
+    fn group_under_the_plain_name_or_the_shortest_member() {
+        let groups = group(&["python3", "python3.11", "python3-config", "gcc", "gcc-12", "ls"].map(String::from), &[]);
+        assert_eq!(groups.children["python3"], ["python3-config", "python3.11"]);
+        assert_eq!(groups.children["gcc"], ["gcc-12"]);
+        assert_eq!(groups.parents["python3.11"], "python3");
+        assert!(!groups.parents.contains_key("ls"));
+        assert!(!groups.children.contains_key("ls"));
+    }
+
+    #[test]
+    fn alternatives_join_the_group_of_their_target() {
+        let links = vec![("cc".to_string(), "gcc-12".to_string()), ("vi".to_string(), "vim.basic".to_string())];
+        let groups = group(&["cc", "gcc", "gcc-12", "vi"].map(String::from), &links);
+        assert_eq!(groups.children["gcc"], ["cc", "gcc-12"]);
+        assert!(!groups.parents.contains_key("vi"));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::process::{output_with_timeout, private_temp_dir};
+use crate::sandbox::destructive_programs;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+pub const VERSION_CACHE_FILE: &str = "cli_organizer_versions.json";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+// Zusätzlich zu den zerstörerischen Programmen aus der Sandbox: hier wäre schon ein Aufruf mit falschem Argument riskant
+const NEVER_PROBE: [&str; 8] = ["halt", "init", "telinit", "kill", "killall", "pkill", "login", "passwd"];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolVersion {
+    // Erste aussagekräftige Zeile der Ausgabe, z. B. "git version 2.39.5"
+    pub raw: String,
+    pub semver: Option<String>,
+    // Womit die Version ermittelt wurde, z. B. "--version"
+    pub probe: String,
+}
+
+// Ergebnis gilt, solange sich Pfad und Änderungszeit der Datei nicht ändern
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedVersion {
+    pub path: PathBuf,
+    pub mtime: u64,
+    pub version: Option<ToolVersion>,
+}
+
+pub struct VersionRequest {
+    pub command: String,
+    pub path: PathBuf,
+    // Nur für Werkzeuge, die einen Unterbefehl "version" haben (go, helm)
+    pub has_version_subcommand: bool,
+}
+
+pub struct VersionProbe {
+    requests: Sender<VersionRequest>,
+    pub results: Receiver<(String, CachedVersion)>,
+}
+
+impl Default for VersionProbe {
+    fn default() -> Self {
+        let (requests, request_receiver) = mpsc::channel::<VersionRequest>();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            for request in request_receiver {
+                let cached = CachedVersion {
+                    mtime: binary_mtime(&request.path).unwrap_or(0),
+                    version: probe(&request),
+                    path: request.path,
+                };
+                if result_sender.send((request.command, cached)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { requests, results }
+    }
+}
+
+impl VersionProbe {
+    pub fn request(&self, request: VersionRequest) {
+        let _ = self.requests.send(request);
+    }
+}
+
+pub fn binary_mtime(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|duration| duration.as_secs())
+}
+
+pub fn is_probe_allowed(command: &str) -> bool {
+    destructive_programs(command).is_empty() && !NEVER_PROBE.contains(&command)
+}
+
+// Probiert --version, ggf. den Unterbefehl `version` und zuletzt -V, das manche Programme als "verbose" verstehen.
+// Läuft in einem frisch angelegten, privaten Temp-Verzeichnis, das auch als HOME dient, und ohne die übrigen
+// Umgebungsvariablen: Ein Programm, das das Argument falsch versteht, legt dort höchstens eine Datei an und findet
+// keine Zugangsdaten. Lässt sich das Verzeichnis nicht anlegen, wird nichts gestartet.
+pub fn probe(request: &VersionRequest) -> Option<ToolVersion> {
+    let mut probes = vec!["--version"];
+    if request.has_version_subcommand {
+        probes.push("version");
+    }
+    probes.push("-V");
+    let dir = match private_temp_dir("cli_organizer_version") {
+        Ok(dir) => dir,
+        Err(e) => {
+            error!("Failed to create a directory for probing {}: {}", request.command, e);
+            return None;
+        }
+    };
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let version = probes.into_iter().find_map(|argument| {
+        let mut command = Command::new(&request.path);
+        command
+            .arg(argument)
+            .current_dir(&dir)
+            .env_clear()
+            .env("PATH", &path)
+            .env("HOME", &dir)
+            .env("LC_ALL", "C");
+        let output = output_with_timeout(&mut command, PROBE_TIMEOUT).ok()?;
+        // Viele ältere Programme schreiben die Version nach stderr
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push('\n');
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        parse_version_output(&text, argument)
+    });
+    let _ = fs::remove_dir_all(&dir);
+    debug!("Version of {}: {:?}", request.command, version.as_ref().map(|version| &version.raw));
+    version
+}
+
+fn parse_version_output(text: &str, probe: &str) -> Option<ToolVersion> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && parse_semver(line).is_some())?;
+    // Fehlermeldungen wie "unknown option -V ... usage 1.0" sind keine Versionsangabe
+    let lower = line.to_lowercase();
+    if lower.contains("unknown") || lower.contains("invalid") || lower.contains("unrecognized") || lower.contains("usage") {
+        return None;
+    }
+    Some(ToolVersion { raw: line.to_string(), semver: parse_semver(line), probe: probe.to_string() })
+}
+
+// Erste Zahl mit Punkt in der Zeile, auf MAJOR.MINOR.PATCH ergänzt: "v1.28" -> "1.28.0", "2.39.5-rc1" bleibt.
+// Ein vorangestellter Name wie in "jq-1.6", "go1.22.5" oder "OpenSSH_9.2p1" wird übersprungen.
+pub fn parse_semver(text: &str) -> Option<String> {
+    for word in text.split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '"' | '\'' | '/')) {
+        let word = word.trim_start_matches(|c: char| c.is_ascii_alphabetic()).trim_start_matches(['-', '_']);
+        let core_end = word
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(word.len());
+        let core = word[..core_end].trim_end_matches('.');
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() < 2 || parts.iter().any(|part| part.is_empty()) {
+            continue;
+        }
+        let mut numbers: Vec<String> = parts.iter().take(3).map(|part| part.trim_start_matches('0').to_string()).collect();
+        for number in &mut numbers {
+            if number.is_empty() {
+                *number = "0".to_string();
+            }
+        }
+        while numbers.len() < 3 {
+            numbers.push("0".to_string());
+        }
+        let mut semver = numbers.join(".");
+        // Vorabversion bzw. Build-Metadaten wie "-rc1" oder "+deb12u3" übernehmen
+        let rest = &word[core_end..];
+        if let Some(suffix) = rest.strip_prefix(['-', '+']) {
+            let suffix: String = suffix.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '-').collect();
+            if !suffix.is_empty() {
+                semver.push(rest.chars().next().unwrap_or('-'));
+                semver.push_str(&suffix);
+            }
+        }
+        return Some(semver);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn semver(line: &str) -> Option<String> {
+        parse_version_output(line, "--version").and_then(|version| version.semver)
+    }
+
+    #[test]
+    fn semver_from_real_version_lines() {
+        assert_eq!(semver("git version 2.39.5").as_deref(), Some("2.39.5"));
+        assert_eq!(semver("Python 3.11.2").as_deref(), Some("3.11.2"));
+        assert_eq!(semver("rustc 1.79.0 (129f3b996 2024-06-10)").as_deref(), Some("1.79.0"));
+        assert_eq!(semver("curl 7.88.1 (x86_64-pc-linux-gnu) libcurl/7.88.1").as_deref(), Some("7.88.1"));
+        assert_eq!(semver("ls (GNU coreutils) 9.1").as_deref(), Some("9.1.0"));
+        assert_eq!(semver("jq-1.6").as_deref(), Some("1.6.0"));
+        assert_eq!(semver("go version go1.22.5 linux/amd64").as_deref(), Some("1.22.5"));
+        assert_eq!(semver("kubectl Client Version: v1.28.2").as_deref(), Some("1.28.2"));
+        assert_eq!(semver("helm v3.14").as_deref(), Some("3.14.0"));
+        assert_eq!(semver("OpenSSH_9.2p1 Debian-2+deb12u3, OpenSSL 3.0.14 4 Jun 2024").as_deref(), Some("9.2.0"));
+        assert_eq!(semver("gcc (Debian 12.2.0-14) 12.2.0").as_deref(), Some("12.2.0-14"));
+        assert_eq!(semver("x86_64-pc-linux-gnu built 2024-06-10").as_deref(), None);
+        assert_eq!(semver("node v20.11.0-rc1").as_deref(), Some("20.11.0-rc1"));
+        assert_eq!(semver("vim 9.0.1378+deb12u1").as_deref(), Some("9.0.1378+deb12u1"));
+    }
+
+    #[test]
+    fn first_line_with_a_version_wins() {
+        let version = parse_version_output("GNU Make 4.3\nBuilt for x86_64-pc-linux-gnu\nCopyright (C) 1988-2020", "--version").unwrap();
+        assert_eq!(version.raw, "GNU Make 4.3");
+        assert_eq!(version.semver.as_deref(), Some("4.3.0"));
+        assert_eq!(version.probe, "--version");
+    }
+
+    #[test]
+    fn error_messages_are_not_versions() {
+        assert!(parse_version_output("tool: unknown option -V\nusage: tool 1.0 [-h]", "-V").is_none());
+        assert!(parse_version_output("invalid option 2.0", "-V").is_none());
+        assert!(parse_version_output("", "--version").is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::cli::command_path;
+use crate::manpage::page_descriptions;
+use crate::package::PackageIndex;
+use crate::version::binary_mtime;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SNAPSHOT_FILE: &str = "cli_organizer_snapshot.json";
+const DAY: u64 = 24 * 60 * 60;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub path: PathBuf,
+    pub mtime: u64,
+}
+
+// Stand des Katalogs zum Zeitpunkt, an dem der Benutzer die Neuerungen zuletzt als gesehen markiert hat
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    // Sekunden seit 1970
+    pub taken_at: u64,
+    pub commands: HashMap<String, SnapshotEntry>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Changed,
+    Removed,
+    // Nicht neu gegenüber dem Schnappschuss, aber die Datei wurde kürzlich geändert
+    Recent,
+}
+
+impl Change {
+    pub fn label(self) -> &'static str {
+        match self {
+            Change::Added => "neu",
+            Change::Changed => "geändert",
+            Change::Removed => "entfernt",
+            Change::Recent => "kürzlich geändert",
+        }
+    }
+}
+
+pub struct NewEntry {
+    pub command: String,
+    pub change: Change,
+    pub path: Option<PathBuf>,
+    pub mtime: Option<u64>,
+    pub description: Option<String>,
+    pub package: Option<String>,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+// Vergleicht den aktuellen Stand mit dem Schnappschuss und nimmt zusätzlich alles auf, dessen Datei
+// in den letzten `recent_days` Tagen geändert wurde. Liefert den aktuellen Stand als neuen Schnappschuss mit.
+pub fn scan(commands: &[String], previous: Option<&Snapshot>, recent_days: u64) -> (Snapshot, Vec<NewEntry>) {
+    let current = Snapshot {
+        taken_at: now(),
+        commands: commands
+            .iter()
+            .filter_map(|command| {
+                let path = command_path(command)?;
+                let mtime = binary_mtime(&path).unwrap_or(0);
+                Some((command.clone(), SnapshotEntry { path, mtime }))
+            })
+            .collect(),
+    };
+    let since = now().saturating_sub(recent_days * DAY);
+    let mut changes: Vec<(String, Change)> = Vec::new();
+    for (command, entry) in &current.commands {
+        let change = match previous.map(|previous| previous.commands.get(command)) {
+            Some(None) => Some(Change::Added),
+            Some(Some(old)) if old != entry => Some(Change::Changed),
+            _ if entry.mtime >= since => Some(Change::Recent),
+            _ => None,
+        };
+        if let Some(change) = change {
+            changes.push((command.clone(), change));
+        }
+    }
+    if let Some(previous) = previous {
+        changes.extend(
+            previous
+                .commands
+                .keys()
+                .filter(|command| !current.commands.contains_key(*command))
+                .map(|command| (command.clone(), Change::Removed)),
+        );
+    }
+
+    let descriptions = if changes.is_empty() { HashMap::new() } else { page_descriptions() };
+    let mut index = PackageIndex::default();
+    let mut entries: Vec<NewEntry> = changes
+        .into_iter()
+        .map(|(command, change)| {
+            let entry = current
+                .commands
+                .get(&command)
+                .or_else(|| previous.and_then(|previous| previous.commands.get(&command)));
+            let package = (change != Change::Removed)
+                .then(|| entry.and_then(|entry| index.lookup(&entry.path)))
+                .flatten();
+            NewEntry {
+                description: descriptions
+                    .get(&command)
+                    .cloned()
+                    .or_else(|| package.as_ref().and_then(|package| package.summary.clone())),
+                package: package.map(|package| match package.version {
+                    Some(version) => format!("{} {}", package.name, version),
+                    None => package.name,
+                }),
+                path: entry.map(|entry| entry.path.clone()),
+                mtime: entry.map(|entry| entry.mtime),
+                change,
+                command,
+            }
+        })
+        .collect();
+    // Neueste Dateien zuerst, entfernte Befehle am Ende
+    entries.sort_by_key(|entry| (entry.change == Change::Removed, std::cmp::Reverse(entry.mtime)));
+    debug!("Found {} new or changed commands", entries.len());
+    (current, entries)
+}